@@ -1,2 +1,63 @@
 app-name = Cosmic Launcher
 type-to-search = Type to search apps or type “?” for more options...
+preview-path = Path
+preview-size = Size
+preview-modified = Modified
+recent-results = Recent results
+input-truncated = Input shortened to {$max} characters
+sticky-mode = Sticky: stays open after launching
+filter-options = Filter options...
+copy-desktop-path = Copy .desktop path
+open-on-new-workspace = Open on new workspace
+copy-launch-command = Copy launch command
+launch-with-arguments = Launch with arguments…
+arguments-for = Arguments for {$app}, then Enter to launch
+filter-apps = Apps
+filter-files = Files
+filter-windows = Windows
+filter-web = Web
+opened-just-now = Opened just now
+opened-minutes-ago = Opened {$minutes ->
+    [one] a minute
+    *[other] {$minutes} minutes
+} ago
+opened-hours-ago = Opened {$hours ->
+    [one] an hour
+    *[other] {$hours} hours
+} ago
+opened-days-ago = Opened {$days ->
+    [one] a day
+    *[other] {$days} days
+} ago
+add-to-startup = Add to startup
+remove-from-startup = Remove from startup
+autostart-added = {$app} will start on login
+autostart-removed = {$app} won't start on login anymore
+autostart-failed = Failed to change whether {$app} starts on login
+confirm-clear-history = Press again to clear the launch and search history
+history-cleared = Launch and search history cleared
+history-clear-failed = Failed to clear the launch and search history
+show-more-results = Show {$count} more {$count ->
+    [one] result
+    *[other] results
+}…
+slot-assigned = {$name} assigned to Alt + {$slot}
+slot-not-an-app = Only apps can be assigned to quick slots, not {$name}
+confirm-option = {$option}?
+cancel = Cancel
+typing-unavailable = Typing results requires {$command} to be installed
+search-web = Search the web for “{$query}”
+source-calculator = Calculator
+source-converter = Converter
+exchange-rates = Exchange rates of {$date}
+exchange-rates-stale = Exchange rates of {$date}, possibly outdated
+source-scratchpad = Scratchpad
+source-commands = Command
+command-toggle-recents = Toggle recent results
+command-toggle-sticky = Toggle sticky mode
+command-toggle-hidden = Toggle hidden apps
+command-zoom-in = Zoom in
+command-zoom-out = Zoom out
+command-clear-history = Clear launch and search history
+source-hidden = Hidden
+hidden-entry = Hidden app · {$id}
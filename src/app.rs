@@ -1,8 +1,26 @@
-use crate::{app::iced::event::listen_raw, components, fl, subscriptions::launcher};
+use crate::{
+    app::iced::event::listen_raw,
+    components,
+    config::{Config, CopyField, CopyOnActivate, CustomAction, Margin, MAX_UI_SCALE, MIN_UI_SCALE},
+    emoji::{self, Emoji},
+    fl, fold, highlight,
+    history::SearchHistory,
+    keybinds::Action,
+    markup,
+    providers::{self, Converter, HiddenApps, SyntheticAction, SyntheticProvider, SyntheticResult},
+    recent::{self, RecentFiles},
+    slots::QuickSlots,
+    subscriptions::launcher,
+    usage::{LastLaunch, UsageStore},
+    workspaces::{self, Workspaces},
+};
 use clap::Parser;
 use cosmic::{
     app::{command, Command, Core, CosmicFlags, DbusActivationDetails, Settings},
-    cctk::sctk,
+    cctk::sctk::{self, reexports::client::protocol::wl_output::WlOutput},
+    cosmic_config::{self, CosmicConfigEntry},
+    cosmic_theme::{ThemeMode, THEME_MODE_ID},
+    desktop::DesktopEntryData,
     iced::{
         self,
         alignment::{Horizontal, Vertical},
@@ -10,19 +28,29 @@ use cosmic::{
         id::Id,
         wayland::{
             actions::{
-                layer_surface::SctkLayerSurfaceSettings,
+                data_device::DataFromMimeType,
+                layer_surface::{IcedOutput, SctkLayerSurfaceSettings},
                 popup::{SctkPopupSettings, SctkPositioner},
             },
             layer_surface::{
-                destroy_layer_surface, get_layer_surface, Anchor, KeyboardInteractivity,
+                destroy_layer_surface, get_layer_surface, set_anchor, set_keyboard_interactivity,
+                set_layer, set_size, Anchor, KeyboardInteractivity, Layer,
             },
         },
-        widget::{column, container, Column},
+        widget::{
+            column, container,
+            scrollable::{AbsoluteOffset, RelativeOffset},
+            Column,
+        },
         Length, Subscription,
     },
     iced_core::{keyboard::key::Named, Border, Padding, Point, Rectangle, Shadow},
     iced_runtime::core::{
-        event::{wayland, wayland::LayerEvent, PlatformSpecific},
+        event::{
+            wayland,
+            wayland::{LayerEvent, OutputEvent},
+            PlatformSpecific,
+        },
         layout::Limits,
         window::Id as SurfaceId,
     },
@@ -39,16 +67,29 @@ use cosmic::{
     },
     Element, Theme,
 };
-use iced::{keyboard::Key, widget::vertical_space, Alignment, Color};
+use iced::{
+    font,
+    keyboard::{Key, Modifiers},
+    widget::vertical_space,
+    Alignment, Color, Font,
+};
 use once_cell::sync::Lazy;
 use pop_launcher::{ContextOption, GpuPreference, IconSource, SearchResult};
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, rc::Rc, str::FromStr, time::Instant};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    path::{Path, PathBuf},
+    rc::Rc,
+    str::FromStr,
+    time::{Duration, Instant, SystemTime},
+};
 use tokio::sync::mpsc;
 use unicode_truncate::UnicodeTruncateStr;
 use unicode_width::UnicodeWidthStr;
 
 static INPUT_ID: Lazy<Id> = Lazy::new(|| Id::new("input_id"));
+static RESULTS_SCROLL_ID: Lazy<Id> = Lazy::new(|| Id::new("results_scroll"));
+static MENU_SCROLL_ID: Lazy<Id> = Lazy::new(|| Id::new("menu_scroll"));
 static RESULT_IDS: Lazy<[Id; 10]> = Lazy::new(|| {
     (0..10)
         .map(|id| Id::new(id.to_string()))
@@ -57,20 +98,70 @@ static RESULT_IDS: Lazy<[Id; 10]> = Lazy::new(|| {
         .unwrap()
 });
 pub(crate) static WINDOW_ID: Lazy<SurfaceId> = Lazy::new(SurfaceId::unique);
+/// Transparent surface behind the launcher which hides it when clicked.
+static BACKDROP_ID: Lazy<SurfaceId> = Lazy::new(SurfaceId::unique);
 pub(crate) static MENU_ID: Lazy<SurfaceId> = Lazy::new(SurfaceId::unique);
 
+/// Approximate height of a result row, including its divider.
+const RESULT_ROW_HEIGHT: f32 = 57.0;
+/// Approximate height of the window padding and the search input above the results.
+const WINDOW_CHROME_HEIGHT: f32 = 96.0;
+const URI_LIST_MIME: &str = "text/uri-list";
+
+const FADE_IN_DURATION: Duration = Duration::from_millis(150);
+const ACTIVATION_FLASH_DURATION: Duration = Duration::from_millis(120);
+const UI_SCALE_STEP: f32 = 0.1;
+/// Labels of the results activated after the leader keybinding, in the order of the results.
+const LETTER_LABELS: &str = "asdfghjklqwertyuiopzxcvbnm";
+/// Types its arguments with the virtual keyboard protocol.
+const TYPE_COMMAND: &str = "wtype";
+/// Program playing the events of the freedesktop sound theme.
+const SOUND_COMMAND: &str = "canberra-gtk-play";
+/// Time for the previously focused app to regain the keyboard focus before typing into it.
+const TYPE_DELAY: Duration = Duration::from_millis(150);
+
+/// Number of windows remembered for `windows_recency`.
+const WINDOW_FOCUS_HISTORY: usize = 64;
+/// Time in which a second Escape hides the launcher in sticky mode, with `sticky_double_escape`.
+const DOUBLE_ESCAPE_INTERVAL: Duration = Duration::from_millis(500);
+/// Interval at which requests are retried while pop-launcher's channel is full.
+const SEND_RETRY_INTERVAL: Duration = Duration::from_millis(50);
+/// Width of the preview pane, including its spacing from the results.
+const PREVIEW_WIDTH: f32 = 256.0;
+/// Id of the context option which copies the path of an app's desktop entry, handled locally.
+const COPY_DESKTOP_PATH_OPTION: u32 = u32::MAX;
+/// Id of the context option which adds an app to or removes it from autostart, handled locally.
+const TOGGLE_AUTOSTART_OPTION: u32 = u32::MAX - 1;
+/// Id of the context option which copies the command line launching an app, handled locally.
+const COPY_LAUNCH_COMMAND_OPTION: u32 = u32::MAX - 2;
+/// Id of the context option which asks for arguments to launch an app with, handled locally.
+const LAUNCH_WITH_ARGS_OPTION: u32 = u32::MAX - 3;
+/// Result id of the first synthetic result, with the ids of the others counting down.
+const SYNTHETIC_IDS: u32 = u32::MAX;
+/// Id of the context option which opens a result on a new workspace, handled locally.
+const OPEN_ON_NEW_WORKSPACE_OPTION: u32 = u32::MAX - 4;
+/// Context option id of the first custom action, with the ids of the others counting down.
+const CUSTOM_ACTION_OPTIONS: u32 = u32::MAX - 5;
+
 #[derive(Parser, Debug, Serialize, Deserialize, Clone)]
 #[command(author, version, about, long_about = None)]
 #[command(propagate_version = true)]
 pub struct Args {
     #[clap(subcommand)]
     pub subcommand: Option<LauncherCommands>,
+    /// Show how long pop-launcher took to answer the last search
+    #[clap(long)]
+    pub debug_timing: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, clap::Subcommand)]
 pub enum LauncherCommands {
     #[clap(about = "Toggle the launcher and switch to the alt-tab view")]
     AltTab,
+    #[clap(about = "Print how often applications were launched from the launcher")]
+    Stats,
+    #[clap(about = "Delete the history of apps launched and queries searched from the launcher")]
+    ClearHistory,
 }
 
 impl ToString for LauncherCommands {
@@ -98,13 +189,29 @@ impl CosmicFlags for Args {
 
 pub fn run() -> cosmic::iced::Result {
     let args = Args::parse();
+    match args.subcommand {
+        Some(LauncherCommands::Stats) => {
+            crate::usage::print_stats();
+            return Ok(());
+        }
+        Some(LauncherCommands::ClearHistory) => {
+            if let Err(err) = crate::usage::clear() {
+                eprintln!("failed to clear the launch and search history: {err}");
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    let config = Config::load(<CosmicLauncher as cosmic::Application>::APP_ID);
     cosmic::app::run_single_instance::<CosmicLauncher>(
         Settings::default()
             .antialiasing(true)
             .client_decorations(true)
             .debug(false)
             .default_text_size(16.0)
-            .scale_factor(1.0)
+            .scale_factor(config.ui_scale())
             .no_main_window(true)
             .exit_on_close(false),
         args,
@@ -120,6 +227,141 @@ pub fn menu_button<'a, Message>(
         .width(Length::Fill)
 }
 
+/// Removes the last word of `text`, along with any whitespace after it.
+fn delete_last_word(text: &mut String) {
+    let end = text.trim_end().len();
+    let start = text[..end]
+        .trim_end_matches(|c: char| !c.is_whitespace())
+        .len();
+    text.truncate(start);
+}
+
+/// The digit which activates the result at index `i` with Ctrl, if any: 1 to 9 for the first
+/// nine results and 0 for the tenth.
+fn activation_digit(i: usize) -> Option<usize> {
+    match i {
+        0..=8 => Some(i + 1),
+        9 => Some(0),
+        _ => None,
+    }
+}
+
+/// The index of the result activated by `digit`, the inverse of [`activation_digit`].
+fn digit_activation(digit: u32) -> usize {
+    (digit as usize + 9) % 10
+}
+
+/// Style of the context option selected with the keyboard, which looks hovered.
+fn selected_menu_button() -> Button {
+    Button::Custom {
+        active: Box::new(|focused, theme| {
+            button::StyleSheet::hovered(theme, focused, true, &Button::AppletMenu)
+        }),
+        hovered: Box::new(|focused, theme| {
+            button::StyleSheet::hovered(theme, focused, true, &Button::AppletMenu)
+        }),
+        disabled: Box::new(|theme| button::StyleSheet::disabled(theme, &Button::AppletMenu)),
+        pressed: Box::new(|focused, theme| {
+            button::StyleSheet::pressed(theme, focused, true, &Button::AppletMenu)
+        }),
+    }
+}
+
+/// Text style of a result row, made translucent while the row fades in.
+fn row_text_style(alpha: f32) -> theme::Text {
+    if alpha >= 1.0 {
+        return theme::Text::Custom(|t| cosmic::iced::widget::text::Appearance {
+            color: Some(t.cosmic().on_bg_color().into()),
+        });
+    }
+
+    let mut color: Color = theme::active().cosmic().on_bg_color().into();
+    color.a *= alpha;
+    theme::Text::Color(color)
+}
+
+/// Removes results with the same name as a result ranked above them, e.g. an app offered by
+/// several plugins. Windows are kept, as several windows may share a title.
+fn deduplicate(list: &mut Vec<SearchResult>) {
+    let mut seen = HashSet::new();
+    list.retain(|item| item.window.is_some() || seen.insert(item.name.trim().to_lowercase()));
+}
+
+/// The search input is always drawn focused, optionally with an accent colored border.
+fn search_input_appearance(theme: &Theme, accent: bool) -> text_input::Appearance {
+    let mut appearance = theme.focused(&cosmic::theme::TextInput::Search);
+    if accent {
+        appearance.border_color = theme.cosmic().accent_color().into();
+        appearance.border_width = 1.0;
+    }
+    appearance
+}
+
+/// A description line with its bold and italic markup applied.
+fn markup_line<'a>(line: &str, alpha: f32) -> Element<'a, Message> {
+    let mut spans = markup::parse(line);
+    markup::truncate(&mut spans, 60);
+    row(spans
+        .into_iter()
+        .map(|span| {
+            let font = Font {
+                weight: if span.bold {
+                    font::Weight::Bold
+                } else {
+                    font::Weight::Normal
+                },
+                style: if span.italic {
+                    font::Style::Italic
+                } else {
+                    font::Style::Normal
+                },
+                ..Font::DEFAULT
+            };
+            text(span.text)
+                .font(font)
+                .size(10)
+                .style(row_text_style(alpha))
+                .into()
+        })
+        .collect())
+    .into()
+}
+
+/// A line of a result name, with the parts matching `query` in bold.
+fn highlighted_line<'a>(line: &str, query: &str, alpha: f32) -> Element<'a, Message> {
+    row(highlight::segments(line, query)
+        .into_iter()
+        .map(|(range, highlighted)| {
+            let font = Font {
+                weight: if highlighted {
+                    font::Weight::Bold
+                } else {
+                    font::Weight::Normal
+                },
+                ..Font::DEFAULT
+            };
+            text(line[range].to_string())
+                .font(font)
+                .size(14)
+                .style(row_text_style(alpha))
+                .into()
+        })
+        .collect())
+    .into()
+}
+
+/// Estimates the width needed to fit the longest option name of a context menu.
+#[allow(clippy::cast_precision_loss)]
+fn menu_width(options: &[ContextOption]) -> f32 {
+    let padding = menu_control_padding();
+    let longest = options
+        .iter()
+        .map(|option| option.name.width())
+        .max()
+        .unwrap_or_default();
+    longest as f32 * 8.0 + padding.left + padding.right
+}
+
 pub fn menu_control_padding() -> Padding {
     let theme = cosmic::theme::active();
     let cosmic = theme.cosmic();
@@ -129,36 +371,228 @@ pub fn menu_control_padding() -> Padding {
 #[derive(Clone)]
 pub struct CosmicLauncher {
     core: Core,
+    config: Config,
+    usage: UsageStore,
+    /// Desktop entries of the favorites bar.
+    favorites: Vec<DesktopEntryData>,
     input_value: String,
+    /// Incremented on every input change, so that only the latest debounced search is sent.
+    search_generation: u64,
+    /// When the last typed search was sent, if its results are pending.
+    search_sent: Option<Instant>,
+    /// How long pop-launcher took to answer the last typed search, shown with `--debug-timing`.
+    search_latency: Option<Duration>,
+    debug_timing: bool,
     active_surface: bool,
     launcher_items: Vec<SearchResult>,
+    /// Results past the first ten, shown when expanding the results.
+    more_results: Vec<SearchResult>,
+    /// Show all results of the current query, instead of the first ten.
+    show_all: bool,
+    /// Query used to narrow `launcher_items` locally until the backend responds.
+    local_query: Option<String>,
     tx: Option<mpsc::Sender<launcher::Request>>,
+    /// Requests made before pop-launcher started or while its channel was full, sent once it
+    /// takes them.
+    pending_requests: VecDeque<launcher::Request>,
+    /// A request was queued since pop-launcher (re)started, and warned about.
+    warned_backend_unavailable: bool,
     wait_for_result: bool,
     menu: Option<(u32, Vec<ContextOption>)>,
+    /// The context option awaiting confirmation, by result and option id.
+    confirm_option: Option<(u32, u32)>,
+    /// Desktop entry files of app results, by name, once looked up.
+    desktop_paths: HashMap<String, PathBuf>,
+    /// Recently used files, read once per opening of the launcher.
+    recent_files: Option<RecentFiles>,
+    /// Desktop entries of app results, by name, once looked up.
+    resolved_entries: HashMap<String, Option<ResolvedEntry>>,
+    /// The installed desktop entries by app id, scanned in the background at startup and
+    /// again whenever the launcher is shown.
+    desktop_entries: Option<BTreeMap<String, DesktopEntryData>>,
+    /// Client of the compositor's workspaces, if it lets the launcher manage them.
+    workspaces: Option<Workspaces>,
+    /// Narrows the options of a long context menu.
+    menu_filter: String,
+    /// Sources of results besides pop-launcher.
+    providers: Vec<Rc<dyn SyntheticProvider>>,
+    /// The unit converter among the providers, if enabled.
+    converter: Option<Rc<Converter>>,
+    /// Apps hidden with `NoDisplay`, while toggled to be shown.
+    hidden_apps: Option<Rc<dyn SyntheticProvider>>,
+    /// The shown synthetic results and their providers, by result id.
+    synthetic: HashMap<u32, (SyntheticResult, Rc<dyn SyntheticProvider>)>,
+    /// Index of the context option selected with the keyboard, among the visible options.
+    menu_selected: Option<usize>,
     cursor_position: Option<Point<f32>>,
     focused: usize,
+    /// Show the results for an empty query regardless of the input.
+    show_recents: bool,
+    hovered: Option<usize>,
+    /// The hovered result, once hovered long enough to replace the focused one in the preview.
+    hover_preview: Option<usize>,
+    /// Metadata of the files the results refer to, by id, read as the results arrive with
+    /// `show_preview`.
+    file_previews: HashMap<u32, FilePreview>,
+    /// The activated result, highlighted until the launcher hides.
+    flashing: Option<u32>,
+    /// Vertical scroll offset of the result list.
+    results_offset: f32,
+    /// Kinds of results shown, or all if empty.
+    kind_filter: HashSet<ResultKind>,
+    /// Applications whose grouped windows are listed individually.
+    expanded_groups: HashSet<String>,
+    /// Results of the emoji picker, in the same order as `launcher_items`, while it is active.
+    emojis: Option<Vec<&'static Emoji>>,
+    /// When results which weren't in the previous update appeared, for fading them in.
+    appeared: HashMap<u32, Instant>,
+    cursor_at_end: bool,
+    /// Outcome of the last action handled by the launcher itself, shown below the input.
+    feedback: Option<String>,
+    /// The clear history keybinding was pressed once, and clears it when pressed again.
+    confirm_clear_history: bool,
+    /// Whether the last input exceeded the maximum length and was cut.
+    input_truncated: bool,
+    /// Keep the launcher open and clear the query after every activation.
+    sticky: bool,
+    /// The leader keybinding was pressed, and the next letter activates the result it labels.
+    leader_pending: bool,
+    /// Windows switched to from the launcher, from the most recent.
+    window_focus: Vec<(u32, u32)>,
+    /// The name and desktop entry of the app which the input takes arguments for, and the
+    /// query to restore once done.
+    launch_args: Option<(String, PathBuf, String)>,
+    /// When Escape last cleared the query instead of hiding the launcher, in sticky mode.
+    last_escape: Option<Instant>,
+    history: SearchHistory,
+    quick_slots: QuickSlots,
+    /// Index of the query recalled from the search history.
+    history_index: Option<usize>,
+    anchor: Anchor,
+    /// Whether the backdrop surface was created with the window.
+    backdrop: bool,
+    /// Whether the surface of the launcher exists, even if hidden with `keep_surface`.
+    surface_created: bool,
+    /// Logical sizes of the outputs, for deciding whether to fill them.
+    outputs: HashMap<WlOutput, (i32, i32)>,
     last_hide: Instant,
     alt_tab: bool,
 }
 
+/// The parts of the desktop entry of an app result which are looked up by its name.
+#[derive(Debug, Clone)]
+struct ResolvedEntry {
+    id: String,
+    exec: Option<String>,
+    /// The `Comment` of the entry, if looked up.
+    comment: Option<String>,
+}
+
+/// Metadata of the file a result refers to, shown in the preview pane.
+#[derive(Debug, Clone)]
+struct FilePreview {
+    path: PathBuf,
+    /// Size in bytes, for regular files.
+    size: Option<u64>,
+    modified: Option<SystemTime>,
+}
+
+impl FilePreview {
+    fn of(item: &SearchResult) -> Option<Self> {
+        let path = file_path(item)?;
+        let metadata = std::fs::metadata(&path).ok()?;
+        Some(Self {
+            size: metadata.is_file().then(|| metadata.len()),
+            modified: metadata.modified().ok(),
+            path,
+        })
+    }
+}
+
+/// Categories of results which can be toggled in the type filter bar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum ResultKind {
+    App,
+    File,
+    Window,
+    Web,
+}
+
+impl ResultKind {
+    const ALL: [Self; 4] = [Self::App, Self::File, Self::Window, Self::Web];
+
+    /// Guesses the kind of a result, as pop-launcher doesn't tell which plugin it is from.
+    fn of(item: &SearchResult) -> Option<Self> {
+        if item.window.is_some() {
+            Some(Self::Window)
+        } else if file_path(item).is_some() {
+            Some(Self::File)
+        } else if item.description.starts_with("http://")
+            || item.description.starts_with("https://")
+        {
+            Some(Self::Web)
+        } else if matches!(item.icon, Some(IconSource::Name(_))) {
+            Some(Self::App)
+        } else {
+            None
+        }
+    }
+
+    fn label(self) -> String {
+        match self {
+            Self::App => fl!("filter-apps"),
+            Self::File => fl!("filter-files"),
+            Self::Window => fl!("filter-windows"),
+            Self::Web => fl!("filter-web"),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum Message {
     InputChanged(String),
+    SearchDebounced(u64),
     Backspace,
+    DeleteWord,
     TabPress,
     CompleteFocusedId(Id),
     Activate(Option<usize>),
     Context(usize),
     MenuButton(u32, u32),
     CloseContextMenu,
+    CancelConfirmation,
     CursorMoved(Point<f32>),
+    Hovered(usize),
+    Unhovered(usize),
+    HoverDelayElapsed(usize),
+    StartDrag(usize),
+    LaunchFavorite(usize),
+    ResultsScrolled(f32),
+    DesktopEntries(Vec<DesktopEntryData>),
+    MenuFilterChanged(String),
+    ToggleGroup(String),
+    ToggleKind(ResultKind),
+    ShowAll,
+    ClearClipboard,
+    AnimationTick,
+    RetryRequests,
+    Activated,
+    FlashDone,
     Hide,
+    Escape,
     LauncherEvent(launcher::Event),
     Layer(LayerEvent),
+    Output(OutputEvent, WlOutput),
     KeyboardNav(keyboard_nav::Message),
     ActivationToken(Option<String>, String, String, GpuPreference),
     AltTab,
     AltRelease,
+    KeyPressed(Key, Modifiers),
+    /// A key which the focused search input typed as text.
+    KeyTyped(Key, Modifiers),
+    Action(Action),
+    Config(Config),
+    ThemeChanged,
 }
 
 impl CosmicLauncher {
@@ -166,24 +600,54 @@ impl CosmicLauncher {
         self.input_value.clear();
         self.focused = 0;
         self.alt_tab = false;
-        self.wait_for_result = false;
+        self.show_recents = false;
+        self.hovered = None;
+        self.hover_preview = None;
+        self.flashing = None;
+        self.results_offset = 0.0;
+        self.kind_filter.clear();
+        self.more_results.clear();
+        self.show_all = false;
+        self.recent_files = None;
+        self.expanded_groups.clear();
+        self.appeared.clear();
+        self.emojis = None;
+        self.cursor_at_end = true;
+        self.input_truncated = false;
+        self.feedback = None;
+        self.confirm_clear_history = false;
+        self.leader_pending = false;
+        self.launch_args = None;
+        self.history_index = None;
+        // Cancels the creation of a surface which is still waiting for its first results.
+        let pending = std::mem::take(&mut self.wait_for_result);
 
         // XXX The close will reset the launcher, but the search will restart it so it's ready
         // for the next time it's opened.
-        if let Some(ref sender) = &self.tx {
-            let _res = sender.blocking_send(launcher::Request::Close);
-        }
-
-        if let Some(tx) = &self.tx {
-            let _res = tx.blocking_send(launcher::Request::Search(String::new()));
-        } else {
-            tracing::info!("NOT FOUND");
-        }
+        self.send(launcher::Request::Close);
+        self.send(launcher::Request::Search(String::new()));
 
         if self.active_surface {
             self.active_surface = false;
 
-            let mut commands = vec![destroy_layer_surface(*WINDOW_ID)];
+            if pending {
+                return Command::none();
+            }
+
+            let mut commands = if self.keeps_surface() {
+                // Layer surfaces can't be unmapped, so the kept one shrinks out of the way.
+                vec![
+                    set_keyboard_interactivity(*WINDOW_ID, KeyboardInteractivity::None),
+                    set_layer(*WINDOW_ID, Layer::Background),
+                    set_size(*WINDOW_ID, Some(1), Some(1)),
+                ]
+            } else {
+                self.surface_created = false;
+                vec![destroy_layer_surface(*WINDOW_ID)]
+            };
+            if std::mem::take(&mut self.backdrop) {
+                commands.push(destroy_layer_surface(*BACKDROP_ID));
+            }
             if self.menu.take().is_some() {
                 commands.push(commands::popup::destroy_popup(*MENU_ID));
             }
@@ -193,18 +657,863 @@ impl CosmicLauncher {
         Command::none()
     }
 
+    /// Whether the surface is kept while hidden. The backdrop must be created before the
+    /// window to be stacked below it, so the window isn't kept along with it.
+    fn keeps_surface(&self) -> bool {
+        self.config.keep_surface && !self.config.click_outside_backdrop
+    }
+
+    /// Sends a request to pop-launcher without blocking, or queues it until pop-launcher has
+    /// started or has room for it.
+    fn send(&mut self, request: launcher::Request) {
+        let request = match request {
+            launcher::Request::Search(query) if self.config.fold_search_query => {
+                launcher::Request::Search(fold::fold(&query))
+            }
+            request => request,
+        };
+
+        // There is nothing to close yet, and only the results of the latest search are of interest.
+        if self.tx.is_none() && matches!(request, launcher::Request::Close) {
+            return;
+        } else if matches!(request, launcher::Request::Search(_)) {
+            self.pending_requests
+                .retain(|pending| !matches!(pending, launcher::Request::Search(_)));
+        }
+        self.pending_requests.push_back(request);
+        if self.tx.is_some() {
+            self.flush_requests();
+            return;
+        }
+
+        if self.warned_backend_unavailable {
+            tracing::debug!("pop-launcher not started yet, queueing request");
+        } else {
+            tracing::warn!("pop-launcher has not started yet, requests are queued until it does");
+            self.warned_backend_unavailable = true;
+        }
+    }
+
+    /// Sends the queued requests in order, until pop-launcher's channel is full. The rest are
+    /// retried every `SEND_RETRY_INTERVAL`.
+    fn flush_requests(&mut self) {
+        let Some(tx) = &self.tx else {
+            return;
+        };
+        // Kept for the restarted service, which announces itself with a new channel.
+        if flush_queue(&mut self.pending_requests, tx) {
+            self.tx = None;
+        } else if !self.pending_requests.is_empty() {
+            tracing::debug!("pop-launcher is busy, queueing request");
+        }
+    }
+
+    /// Hides the launcher after an activation, or only resets the query in sticky mode.
+    fn finish_activation(&mut self) -> Command<Message> {
+        if !self.sticky && self.flashing.is_some() {
+            return Command::perform(tokio::time::sleep(ACTIVATION_FLASH_DURATION), |()| {
+                cosmic::app::message::app(Message::FlashDone)
+            });
+        } else if !self.sticky {
+            return self.hide();
+        }
+
+        self.flashing = None;
+        self.input_value.clear();
+        self.local_query = None;
+        self.focused = 0;
+        self.show_recents = false;
+        self.hovered = None;
+        self.emojis = None;
+        self.cursor_at_end = true;
+        self.input_truncated = false;
+        self.send(launcher::Request::Search(String::new()));
+
+        let mut commands = vec![text_input::focus(INPUT_ID.clone())];
+        commands.extend(self.close_menu());
+        Command::batch(commands)
+    }
+
+    /// Selects the whole query if it was filled in and `select_prefilled_query` is enabled.
+    fn select_prefilled(&self) -> Option<Command<Message>> {
+        (self.config.select_prefilled_query && !self.input_value.is_empty()).then(|| {
+            Command::batch([
+                text_input::focus(INPUT_ID.clone()),
+                text_input::select_all(INPUT_ID.clone()),
+            ])
+        })
+    }
+
+    /// The index of the recalled query, if the input still holds it.
+    fn recalled_query(&self) -> Option<usize> {
+        self.history_index
+            .filter(|i| self.history.queries.get(*i) == Some(&self.input_value))
+    }
+
+    /// Whether Up and Down recall queries from the history instead of moving the focus, until
+    /// the results of a recalled query arrive.
+    fn browsing_history(&self) -> bool {
+        self.config.search_history
+            && self.launcher_items.is_empty()
+            && (self.recalled_query().is_some()
+                || (self.input_value.is_empty() && !self.history.queries.is_empty()))
+    }
+
+    /// Fills the input with an older or newer query from the history, or empties it past the
+    /// most recent one.
+    fn recall_query(&mut self, older: bool) -> Command<Message> {
+        let last = self.history.queries.len().saturating_sub(1);
+        let index = match (self.recalled_query(), older) {
+            (None, true) => Some(0),
+            (None, false) | (Some(0), false) => None,
+            (Some(i), true) => Some((i + 1).min(last)),
+            (Some(i), false) => Some(i - 1),
+        };
+        let query = index
+            .and_then(|i| self.history.queries.get(i).cloned())
+            .unwrap_or_default();
+        let command = self.update(Message::InputChanged(query));
+        self.history_index = index;
+        Command::batch([command, text_input::move_cursor_to_end(INPUT_ID.clone())])
+    }
+
+    /// Whether the context menu is long enough to show the field for filtering its options.
+    fn menu_filter_shown(&self) -> bool {
+        self.menu.as_ref().map_or(false, |(_, options)| {
+            self.config
+                .context_menu
+                .filter_above
+                .map_or(false, |max| options.len() > max)
+        })
+    }
+
+    /// The options of the context menu which match the filter, if shown.
+    fn visible_menu_options(&self) -> Vec<&ContextOption> {
+        let show_filter = self.menu_filter_shown();
+        self.menu
+            .iter()
+            .flat_map(|(_, options)| options)
+            .filter(|option| !show_filter || fuzzy_matches(&self.menu_filter, &option.name))
+            .collect()
+    }
+
+    /// Moves the keyboard selection of the context menu, scrolling it into view.
+    #[allow(clippy::cast_precision_loss)]
+    fn move_menu_selection(&mut self, forward: bool) -> Command<Message> {
+        let len = self.visible_menu_options().len();
+        if len == 0 {
+            return Command::none();
+        }
+        let selected = match (self.menu_selected, forward) {
+            (None, true) => 0,
+            (None, false) => len - 1,
+            (Some(i), true) => (i + 1) % len,
+            (Some(i), false) => (i + len - 1) % len,
+        };
+        self.menu_selected = Some(selected);
+        iced::widget::scrollable::snap_to(
+            MENU_SCROLL_ID.clone(),
+            RelativeOffset {
+                x: 0.0,
+                y: selected as f32 / (len - 1).max(1) as f32,
+            },
+        )
+    }
+
+    /// Whether the context option must be confirmed before acting on it, and isn't yet.
+    fn needs_confirmation(&self, id: u32, option: u32) -> bool {
+        if self.confirm_option == Some((id, option)) {
+            return false;
+        }
+        let Some((_, options)) = self.menu.as_ref() else {
+            return false;
+        };
+        options.iter().find(|o| o.id == option).map_or(false, |o| {
+            let name = o.name.to_lowercase();
+            self.config
+                .confirm_context_options
+                .iter()
+                .any(|pattern| name.contains(&pattern.to_lowercase()))
+        })
+    }
+
+    /// The custom action of the context option with the given id, if it is one.
+    fn custom_action(&self, option: u32) -> Option<&CustomAction> {
+        let index = CUSTOM_ACTION_OPTIONS.checked_sub(option)?;
+        self.config.custom_actions.get(index as usize)
+    }
+
+    /// The desktop entry file of the app result with the given id, looked up by its name.
+    fn desktop_path(&mut self, id: u32) -> Option<PathBuf> {
+        let item = self
+            .launcher_items
+            .iter()
+            .find(|item| item.id == id && item.window.is_none())?;
+        if self.emojis.is_some() {
+            return None;
+        }
+        if let Some(path) = self.desktop_paths.get(&item.name) {
+            return Some(path.clone());
+        }
+
+        let path = self.desktop_entry_named(&item.name)?.path.clone()?;
+        self.desktop_paths.insert(item.name.clone(), path.clone());
+        Some(path)
+    }
+
+    /// Whether the desktop entries of app results are needed to show or rank them.
+    fn needs_entries(&self) -> bool {
+        self.config.show_exec
+            || self.config.comment_descriptions
+            || self.config.frecency_ranking
+            || !self.quick_slots.slots.is_empty()
+    }
+
+    /// The installed desktop entry with the given name, once the entries are scanned.
+    fn desktop_entry_named(&self, name: &str) -> Option<&DesktopEntryData> {
+        self.desktop_entries
+            .as_ref()?
+            .values()
+            .find(|entry| entry.name == name)
+    }
+
+    /// Looks up the desktop entries of app results which weren't looked up before. Nothing is
+    /// looked up before the entries are scanned, as the results are resolved again then.
+    fn resolve_entries(&mut self, items: &[SearchResult]) {
+        if self.desktop_entries.is_none() {
+            return;
+        }
+        let missing: Vec<_> = items
+            .iter()
+            .filter(|item| item.window.is_none() && !self.resolved_entries.contains_key(&item.name))
+            .map(|item| item.name.clone())
+            .collect();
+        if missing.is_empty() {
+            return;
+        }
+
+        for name in missing {
+            let entry = self.desktop_entry_named(&name).map(|entry| ResolvedEntry {
+                id: entry.id.to_string(),
+                exec: entry.exec.clone(),
+                comment: entry
+                    .path
+                    .as_deref()
+                    .filter(|_| self.config.comment_descriptions)
+                    .and_then(|path| desktop_entry_value(path, "Comment")),
+            });
+            self.resolved_entries.insert(name, entry);
+        }
+    }
+
+    /// Position of the window of a result in the focus history, if `windows_recency` is enabled.
+    fn focus_rank(&self, item: &SearchResult) -> usize {
+        item.window
+            .filter(|_| self.config.windows_recency)
+            .and_then(|window| self.window_focus.iter().position(|w| *w == window))
+            .unwrap_or(usize::MAX)
+    }
+
+    /// How likely the app of a result is to be launched again, from the launch history.
+    fn frecency(&self, item: &SearchResult) -> f32 {
+        self.resolved_entries
+            .get(&item.name)
+            .and_then(Option::as_ref)
+            .map_or(0.0, |entry| {
+                self.usage.frecency(&entry.id, self.config.frecency_weights)
+            })
+    }
+
+    /// Changes the UI scale by `delta` and saves it to the config.
+    fn zoom(&mut self, delta: f32) -> Command<Message> {
+        let scale = (self.config.ui_scale() + delta).clamp(MIN_UI_SCALE, MAX_UI_SCALE);
+        let app_id = <Self as cosmic::Application>::APP_ID;
+        match cosmic_config::Config::new(app_id, Config::VERSION) {
+            Ok(helper) => {
+                if let Err(err) = self.config.set_ui_scale(&helper, scale) {
+                    tracing::error!("failed to save UI scale: {err}");
+                }
+            }
+            Err(err) => tracing::error!("failed to open launcher config: {err}"),
+        }
+        self.core.set_scale_factor(scale);
+        Command::none()
+    }
+
+    /// Scrolls the result list by `delta` pixels without moving the focus, stopping at the
+    /// last row.
+    #[allow(clippy::cast_precision_loss)]
+    fn scroll_results(&mut self, delta: f32) -> Command<Message> {
+        let content_height = self.visible_items().count() as f32 * RESULT_ROW_HEIGHT;
+        let max_offset = (content_height - self.config.max_results_height).max(0.0);
+        self.results_offset = (self.results_offset + delta).clamp(0.0, max_offset);
+        iced::widget::scrollable::scroll_to(
+            RESULTS_SCROLL_ID.clone(),
+            AbsoluteOffset {
+                x: 0.0,
+                y: self.results_offset,
+            },
+        )
+    }
+
+    /// The copy-on-activate rule matching the prefix of the current query, if any.
+    fn copy_rule(&self) -> Option<CopyOnActivate> {
+        self.config
+            .copy_on_activate
+            .iter()
+            .find(|rule| self.input_value.starts_with(&rule.prefix))
+            .cloned()
+    }
+
+    fn fade_alpha(&self, id: u32) -> f32 {
+        self.appeared.get(&id).map_or(1.0, |appeared| {
+            (appeared.elapsed().as_secs_f32() / FADE_IN_DURATION.as_secs_f32()).min(1.0)
+        })
+    }
+
+    /// Replaces the results with the emojis matching `query`, bypassing the backend.
+    #[allow(clippy::cast_possible_truncation)]
+    fn show_emojis(&mut self, query: &str) {
+        let emojis: Vec<_> = emoji::search(query).take(10).collect();
+        self.launcher_items = emojis
+            .iter()
+            .enumerate()
+            .map(|(i, emoji)| SearchResult {
+                id: i as u32,
+                name: emoji.name.to_string(),
+                description: String::new(),
+                icon: None,
+                category_icon: None,
+                window: None,
+            })
+            .collect();
+        self.emojis = Some(emojis);
+        self.more_results.clear();
+        self.file_previews.clear();
+        self.local_query = None;
+        self.focused = 0;
+    }
+
+    fn emoji(&self, item: &SearchResult) -> Option<&'static Emoji> {
+        self.emojis.as_ref()?.get(item.id as usize).copied()
+    }
+
+    /// Destroys the context menu popup, if one is open.
+    fn close_menu(&mut self) -> Option<Command<Message>> {
+        self.confirm_option = None;
+        self.menu_selected = None;
+        // The filter field of the menu may have taken the focus from the search input.
+        self.menu.take().map(|_| {
+            Command::batch([
+                commands::popup::destroy_popup(*MENU_ID),
+                text_input::focus(INPUT_ID.clone()),
+            ])
+        })
+    }
+
     fn focus_next(&mut self) {
-        if self.launcher_items.is_empty() {
+        let len = self.visible_items().count();
+        if len == 0 {
             return;
         }
-        self.focused = (self.focused + 1) % self.launcher_items.len();
+        self.focused = (self.focused + 1) % len;
     }
 
     fn focus_previous(&mut self) {
-        if self.launcher_items.is_empty() {
+        let len = self.visible_items().count();
+        if len == 0 {
             return;
         }
-        self.focused = (self.focused + self.launcher_items.len() - 1) % self.launcher_items.len();
+        self.focused = (self.focused + len - 1) % len;
+    }
+
+    /// The results currently shown, narrowed by the local filter and the type filter if
+    /// active, and without the windows of collapsed groups.
+    fn visible_items(&self) -> impl Iterator<Item = &SearchResult> + '_ {
+        self.launcher_items
+            .iter()
+            .filter(move |item| self.is_visible(item))
+    }
+
+    fn is_visible(&self, item: &SearchResult) -> bool {
+        let matches = self.local_query.as_deref().map_or(true, |query| {
+            fuzzy_matches(query, &item.name) || fuzzy_matches(query, &item.description)
+        });
+        let kind_shown = self.kind_filter.is_empty()
+            || self
+                .kind_of(item)
+                .map_or(false, |kind| self.kind_filter.contains(&kind));
+        matches && kind_shown && !self.is_collapsed_window(item)
+    }
+
+    /// Collects the results of the synthetic providers for the current query, split into those
+    /// shown above and below the results of pop-launcher.
+    fn synthetic_results(&mut self) -> (Vec<SearchResult>, Vec<SearchResult>) {
+        self.synthetic.clear();
+        let query = self.input_value.trim();
+        let mut ids = (0..=SYNTHETIC_IDS).rev();
+        let mut results = Vec::new();
+        for provider in self.providers.iter().chain(&self.hidden_apps) {
+            for result in provider.results(query) {
+                let Some(id) = ids.next() else {
+                    break;
+                };
+                results.push((
+                    provider.priority(),
+                    SearchResult {
+                        id,
+                        name: result.name.clone(),
+                        description: result.description.clone(),
+                        icon: result
+                            .icon
+                            .clone()
+                            .map(|name| IconSource::Name(name.into())),
+                        category_icon: None,
+                        window: None,
+                    },
+                ));
+                self.synthetic.insert(id, (result, provider.clone()));
+            }
+        }
+
+        results.sort_by_key(|(priority, _)| std::cmp::Reverse(*priority));
+        let (above, below): (Vec<_>, Vec<_>) =
+            results.into_iter().partition(|(priority, _)| *priority > 0);
+        (
+            above.into_iter().map(|(_, item)| item).collect(),
+            below.into_iter().map(|(_, item)| item).collect(),
+        )
+    }
+
+    /// Where a result came from, for the source badge. Results of other pop-launcher plugins
+    /// have no badge, as only their icon tells them apart.
+    fn source(&self, item: &SearchResult) -> Option<String> {
+        if let Some(source) = self
+            .synthetic
+            .get(&item.id)
+            .and_then(|(_, provider)| provider.source())
+        {
+            return Some(source);
+        }
+        self.kind_of(item).map(ResultKind::label)
+    }
+
+    /// The kind of a result, as declared by its provider if it is synthetic.
+    fn kind_of(&self, item: &SearchResult) -> Option<ResultKind> {
+        self.synthetic
+            .get(&item.id)
+            .map_or_else(|| ResultKind::of(item), |(_, provider)| provider.kind())
+    }
+
+    /// How many of the results past the first ten would be shown when expanding the results.
+    fn hidden_result_count(&self) -> usize {
+        self.more_results
+            .iter()
+            .filter(|item| self.is_visible(item))
+            .count()
+    }
+
+    /// The windows of the same application as `item`, if it is a window and grouping is enabled.
+    fn window_group<'a>(
+        &'a self,
+        item: &'a SearchResult,
+    ) -> impl Iterator<Item = &'a SearchResult> + 'a {
+        let grouped = self.config.group_windows && item.window.is_some();
+        self.launcher_items
+            .iter()
+            .filter(move |other| grouped && other.window.is_some() && other.name == item.name)
+    }
+
+    /// Whether `item` is a window hidden under the first window of its collapsed group.
+    fn is_collapsed_window(&self, item: &SearchResult) -> bool {
+        !self.expanded_groups.contains(&item.name)
+            && self
+                .window_group(item)
+                .next()
+                .map_or(false, |head| head.id != item.id)
+    }
+
+    fn visible_item(&self, i: usize) -> Option<&SearchResult> {
+        self.visible_items().nth(i)
+    }
+
+    /// Centers the window while its content is short, if configured, and anchors it to the
+    /// top once the list grows so that it extends downward.
+    #[allow(clippy::cast_precision_loss)]
+    fn layer_anchor(&self) -> Anchor {
+        if self.fullscreen() {
+            return Anchor::all();
+        }
+        let anchor = self.config.position.anchor();
+        let Some(max_height) = self.config.center_below_height else {
+            return anchor;
+        };
+
+        let height = WINDOW_CHROME_HEIGHT + self.visible_items().count() as f32 * RESULT_ROW_HEIGHT;
+        if height < max_height {
+            Anchor::empty()
+        } else {
+            anchor
+        }
+    }
+
+    /// Whether the launcher fills the output, because all outputs are too narrow for the window.
+    ///
+    /// The output the launcher opens on is up to the compositor, so only when every output is
+    /// small can it be known to be small.
+    fn fullscreen(&self) -> bool {
+        self.config
+            .fullscreen_below_width
+            .map_or(false, |threshold| {
+                !self.outputs.is_empty()
+                    && self
+                        .outputs
+                        .values()
+                        .all(|(width, _)| u32::try_from(*width).map_or(true, |w| w < threshold))
+            })
+    }
+
+    /// How many results are kept from the backend's response.
+    ///
+    /// Fitted to the shortest output, like `fullscreen`, as the output the launcher opens on
+    /// isn't known beforehand.
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    fn result_limit(&self) -> usize {
+        match self.config.max_results {
+            Some(0) => return usize::MAX,
+            Some(max) => return max,
+            None => {}
+        }
+        let shortest = self.outputs.values().map(|(_, height)| *height).min();
+        match shortest.filter(|_| self.config.fit_results_to_height) {
+            Some(height) => {
+                let margin = self.config.position.margin(Some(height));
+                let available = (height - margin.top - margin.bottom) as f32 - WINDOW_CHROME_HEIGHT;
+                let height = available.min(self.config.max_results_height);
+                ((height / RESULT_ROW_HEIGHT) as usize).max(1)
+            }
+            None => 10,
+        }
+    }
+
+    /// The margin of the window from the anchored edges of the output.
+    ///
+    /// The output the launcher opens on is up to the compositor, so the margin is computed for
+    /// the shortest output, on which the window must fit.
+    fn margin(&self) -> Margin {
+        let shortest = self.outputs.values().map(|(_, height)| *height).min();
+        self.config.position.margin(shortest)
+    }
+
+    fn max_width(&self) -> f32 {
+        if self.fullscreen() {
+            f32::INFINITY
+        } else if self.config.show_preview {
+            self.config.window_width() + PREVIEW_WIDTH
+        } else {
+            self.config.window_width()
+        }
+    }
+
+    /// Looks up an icon in the configured icon theme, if one overrides the system theme.
+    fn themed_icon(&self, name: &str, size: u16) -> Option<icon::Handle> {
+        let theme = self.config.icon_theme.as_deref()?;
+        freedesktop_icons::lookup(name)
+            .with_size(size)
+            .with_theme(theme)
+            .with_cache()
+            .find()
+            .map(icon::from_path)
+    }
+
+    /// The name of the top result, if it extends the current query.
+    fn completion(&self) -> Option<&str> {
+        let name = self.visible_item(0)?.name.as_str();
+        let prefix = name.get(..self.input_value.len())?;
+        (!self.input_value.is_empty()
+            && name.len() > prefix.len()
+            && prefix.to_lowercase() == self.input_value.to_lowercase())
+        .then_some(name)
+    }
+
+    /// Tracks whether the input cursor is at the end of the query, and accepts the
+    /// completion when Right is pressed there.
+    ///
+    /// iced doesn't expose the cursor of the text input, so this is inferred from the
+    /// navigation keys: once moved left, the cursor is assumed to stay away from the end until
+    /// End is pressed or the input is replaced.
+    fn input_cursor_key(&mut self, key: &Key) -> Option<Command<Message>> {
+        match key {
+            Key::Named(Named::ArrowLeft | Named::Home) => self.cursor_at_end = false,
+            Key::Named(Named::End) => self.cursor_at_end = true,
+            Key::Named(Named::ArrowRight)
+                if self.config.autocomplete_on_right && self.cursor_at_end =>
+            {
+                let completion = self.completion()?.to_string();
+                self.input_value = completion.clone();
+                self.send(launcher::Request::Search(completion));
+                return Some(text_input::move_cursor_to_end(INPUT_ID.clone()));
+            }
+            _ => {}
+        }
+        None
+    }
+
+    /// Narrows the displayed results to those matching `query` as a stopgap until the
+    /// backend sends its own results for it.
+    fn local_filter(&mut self, query: &str) {
+        if !self.config.local_filter {
+            return;
+        }
+
+        self.local_query = Some(query.to_string());
+        self.focused = 0;
+    }
+}
+
+/// Case- and accent-insensitive subsequence match of `query` against `text`.
+fn fuzzy_matches(query: &str, text: &str) -> bool {
+    let text = fold::normalize(text);
+    let mut text = text.chars();
+    fold::normalize(query)
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .all(|q| text.any(|t| t == q))
+}
+
+/// Requests an activation token for the desktop entry at `path`, launching it once received.
+///
+/// Entries which must be run in a terminal are wrapped in the `terminal` command.
+fn activate_desktop_entry(
+    path: PathBuf,
+    gpu_preference: GpuPreference,
+    action_name: Option<String>,
+    args: Option<&str>,
+    terminal: &str,
+) -> Command<Message> {
+    let in_terminal = runs_in_terminal(&path);
+    let flatpak_id = flatpak_id(&path);
+    let Some(entry) = cosmic::desktop::load_desktop_file(None, path) else {
+        return Command::none();
+    };
+
+    let exec = if let Some(action_name) = action_name {
+        entry
+            .desktop_actions
+            .into_iter()
+            .find(|action| action.name == action_name)
+            .map(|action| action.exec)
+    } else {
+        entry.exec
+    };
+
+    let Some(mut exec) = exec else {
+        return Command::none();
+    };
+    if let Some(args) = args.filter(|args| !args.trim().is_empty()) {
+        exec = format!("{exec} {args}");
+    }
+    if let Some(flatpak_id) = flatpak_id.filter(|_| !exec.contains("flatpak")) {
+        // Exported entries normally run the app through flatpak already, but the launch fails
+        // for those which only name the command inside the sandbox.
+        let (command, args) = exec.split_once(' ').unwrap_or((&exec, ""));
+        exec = format!("flatpak run --command={command} {flatpak_id} {args}");
+    }
+    if in_terminal {
+        exec = format!("{terminal} {exec}");
+    }
+
+    request_launch(entry.id.to_string(), exec, gpu_preference)
+}
+
+/// The app id of a desktop entry exported by flatpak, which is named after the app.
+fn flatpak_id(path: &Path) -> Option<String> {
+    if path.extension()? != "desktop" {
+        return None;
+    }
+    path.parent()?
+        .components()
+        .any(|component| component.as_os_str() == "flatpak")
+        .then(|| path.file_stem())
+        .flatten()
+        .map(|stem| stem.to_string_lossy().into_owned())
+}
+
+/// Whether the desktop entry at `path` sets `Terminal=true`.
+fn runs_in_terminal(path: &Path) -> bool {
+    desktop_entry_value(path, "Terminal").as_deref() == Some("true")
+}
+
+/// The unlocalized value of `key` in the `[Desktop Entry]` group of the file at `path`.
+fn desktop_entry_value(path: &Path, key: &str) -> Option<String> {
+    let contents = std::fs::read_to_string(path).ok()?;
+    contents
+        .lines()
+        .skip_while(|line| line.trim() != "[Desktop Entry]")
+        .skip(1)
+        .take_while(|line| !line.starts_with('['))
+        .filter_map(|line| line.split_once('='))
+        .find(|(k, _)| k.trim() == key)
+        .map(|(_, value)| value.trim().to_string())
+}
+
+/// Removes the field codes, such as `%U`, from the `Exec` value of a desktop entry.
+fn strip_field_codes(exec: &str) -> String {
+    exec.split_whitespace()
+        .filter(|arg| !(arg.len() == 2 && arg.starts_with('%') && *arg != "%%"))
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace("%%", "%")
+}
+
+/// Requests an activation token for launching `exec`, launching it once received.
+fn request_launch(app_id: String, exec: String, gpu_preference: GpuPreference) -> Command<Message> {
+    request_token(
+        Some(String::from(
+            <CosmicLauncher as cosmic::Application>::APP_ID,
+        )),
+        Some(*WINDOW_ID),
+        move |token| {
+            cosmic::app::Message::App(Message::ActivationToken(
+                token,
+                app_id,
+                exec,
+                gpu_preference,
+            ))
+        },
+    )
+}
+
+/// Loads the desktop entries of the favorites bar, skipping those which can't be found.
+fn load_favorites(desktop_ids: &[String]) -> Vec<DesktopEntryData> {
+    desktop_ids
+        .iter()
+        .filter_map(|desktop_id| {
+            let entry = find_desktop_entry(desktop_id)
+                .and_then(|path| cosmic::desktop::load_desktop_file(None, path));
+            if entry.is_none() {
+                tracing::warn!("no desktop entry found for favorite: {desktop_id}");
+            }
+            entry
+        })
+        .collect()
+}
+
+/// Where a copy of the desktop entry at `path` starts the app on login.
+fn autostart_path(path: &Path) -> Option<PathBuf> {
+    let file_name = path.file_name()?;
+    let dirs = xdg::BaseDirectories::new().ok()?;
+    Some(dirs.get_config_home().join("autostart").join(file_name))
+}
+
+/// Adds the desktop entry at `path` to the autostart entries, or removes it if it already is,
+/// returning the message describing the outcome.
+fn toggle_autostart(name: &str, path: &Path) -> String {
+    let Some(autostart) = autostart_path(path) else {
+        return fl!("autostart-failed", app = name);
+    };
+
+    let result = if autostart.exists() {
+        std::fs::remove_file(&autostart).map(|()| fl!("autostart-removed", app = name))
+    } else {
+        autostart
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|()| std::fs::copy(path, &autostart))
+            .map(|_| fl!("autostart-added", app = name))
+    };
+
+    result.unwrap_or_else(|err| {
+        tracing::error!("failed to update {}: {err}", autostart.display());
+        fl!("autostart-failed", app = name)
+    })
+}
+
+/// Finds the desktop entry with the given id in the XDG data directories.
+fn find_desktop_entry(desktop_id: &str) -> Option<PathBuf> {
+    let desktop_id = desktop_id.trim_end_matches(".desktop");
+    xdg::BaseDirectories::new()
+        .ok()?
+        .find_data_file(format!("applications/{desktop_id}.desktop"))
+}
+
+/// The local file a result refers to, as file results carry their path in the description.
+fn file_path(item: &SearchResult) -> Option<PathBuf> {
+    let description = item.description.trim();
+    let path = match description.strip_prefix("~/") {
+        Some(relative) => home_dir()?.join(relative),
+        None => PathBuf::from(description),
+    };
+    (path.is_absolute() && path.exists()).then_some(path)
+}
+
+/// Drag source data for dropping a file result into another application.
+struct FileDrag(PathBuf);
+
+impl DataFromMimeType for FileDrag {
+    fn from_mime_type(&self, mime_type: &str) -> Option<Vec<u8>> {
+        (mime_type == URI_LIST_MIME).then(|| file_uri(&self.0).into_bytes())
+    }
+}
+
+/// Formats a path as a `file://` URI, percent-encoding everything but unreserved characters.
+fn file_uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+    for byte in path.as_os_str().as_encoded_bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-_.~".contains(byte) {
+            uri.push(char::from(*byte));
+        } else {
+            uri.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    uri.push_str("\r\n");
+    uri
+}
+
+/// Builds a pane with the metadata of the file a result refers to.
+fn file_preview<'a>(preview: &FilePreview) -> Element<'a, Message> {
+    let mut fields = vec![(fl!("preview-path"), preview.path.display().to_string())];
+    if let Some(size) = preview.size {
+        fields.push((fl!("preview-size"), format_size(size)));
+    }
+    if let Some(modified) = preview.modified {
+        let modified = chrono::DateTime::<chrono::Local>::from(modified);
+        fields.push((
+            fl!("preview-modified"),
+            modified.format("%Y-%m-%d %H:%M").to_string(),
+        ));
+    }
+
+    let fields = fields
+        .into_iter()
+        .map(|(label, value)| column![text::caption(label), text::body(value)].into());
+    Column::with_children(fields)
+        .spacing(8)
+        .width(Length::Fixed(PREVIEW_WIDTH - 16.0))
+        .into()
+}
+
+fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(PathBuf::from)
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} {}", UNITS[0])
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
     }
 }
 
@@ -222,6 +1531,44 @@ async fn launch(token: Option<String>, app_id: String, exec: String, gpu: GpuPre
     cosmic::desktop::spawn_desktop_exec(exec, envs, Some(&app_id)).await;
 }
 
+/// Types into the focused app with `wtype` and its `args`, once the launcher has given up the
+/// keyboard focus.
+async fn wtype(args: Vec<String>) {
+    tokio::time::sleep(TYPE_DELAY).await;
+    if let Err(err) = std::process::Command::new(TYPE_COMMAND).args(args).spawn() {
+        tracing::error!("failed to run {TYPE_COMMAND}: {err}");
+    }
+}
+
+/// Plays the event of the sound theme, if there is a program to play it.
+fn play_sound(event: &str) {
+    if let Err(err) = std::process::Command::new(SOUND_COMMAND)
+        .args(["-i", event])
+        .spawn()
+    {
+        tracing::debug!("failed to run {SOUND_COMMAND}: {err}");
+    }
+}
+
+/// Scans the installed desktop entries without blocking the UI, as there can be many.
+fn load_desktop_entries() -> Command<Message> {
+    Command::perform(
+        async {
+            tokio::task::spawn_blocking(|| cosmic::desktop::load_applications(None, true))
+                .await
+                .unwrap_or_default()
+        },
+        |entries| cosmic::app::message::app(Message::DesktopEntries(entries)),
+    )
+}
+
+/// Whether an executable named `program` is in `PATH`.
+fn in_path(program: &str) -> bool {
+    std::env::var_os("PATH").map_or(false, |paths| {
+        std::env::split_paths(&paths).any(|dir| dir.join(program).is_file())
+    })
+}
+
 async fn try_get_gpu_envs(gpu: GpuPreference) -> Option<HashMap<String, String>> {
     let connection = zbus::Connection::system().await.ok()?;
     let proxy = switcheroo_control::SwitcherooControlProxy::new(&connection)
@@ -242,23 +1589,76 @@ impl cosmic::Application for CosmicLauncher {
     type Flags = Args;
     const APP_ID: &'static str = "com.system76.CosmicLauncher";
 
-    fn init(mut core: Core, _flags: Args) -> (Self, Command<Message>) {
+    fn init(mut core: Core, flags: Args) -> (Self, Command<Message>) {
         core.set_keyboard_nav(false);
+        // Loaded before the first surface is created so that it opens at the saved position.
+        let config = Config::load(Self::APP_ID);
+        let converter = providers::converter(&config);
         (
             CosmicLauncher {
                 core,
+                anchor: config.position.anchor(),
+                sticky: config.sticky,
+                favorites: load_favorites(&config.favorites),
+                providers: providers::builtin(&config, converter.as_ref()),
+                converter,
+                hidden_apps: None,
+                window_focus: Vec::new(),
+                launch_args: None,
+                last_escape: None,
+                history: SearchHistory::load(),
+                quick_slots: QuickSlots::load(),
+                history_index: None,
+                synthetic: HashMap::new(),
+                config,
+                usage: UsageStore::load(),
                 input_value: String::new(),
+                search_generation: 0,
+                search_sent: None,
+                search_latency: None,
+                debug_timing: flags.debug_timing,
                 active_surface: false,
                 launcher_items: Vec::new(),
+                more_results: Vec::new(),
+                show_all: false,
+                local_query: None,
                 tx: None,
+                pending_requests: VecDeque::new(),
+                warned_backend_unavailable: false,
                 wait_for_result: false,
                 menu: None,
+                menu_filter: String::new(),
+                leader_pending: false,
+                menu_selected: None,
+                desktop_paths: HashMap::new(),
+                resolved_entries: HashMap::new(),
+                desktop_entries: None,
+                workspaces: Workspaces::spawn(),
+                recent_files: None,
                 cursor_position: None,
+                confirm_option: None,
+                outputs: HashMap::new(),
+                backdrop: false,
+                surface_created: false,
                 focused: 0,
+                show_recents: false,
+                hovered: None,
+                hover_preview: None,
+                file_previews: HashMap::new(),
+                flashing: None,
+                results_offset: 0.0,
+                kind_filter: HashSet::new(),
+                expanded_groups: HashSet::new(),
+                emojis: None,
+                appeared: HashMap::new(),
+                cursor_at_end: true,
+                feedback: None,
+                confirm_clear_history: false,
+                input_truncated: false,
                 last_hide: Instant::now(),
                 alt_tab: false,
             },
-            Command::none(),
+            load_desktop_entries(),
         )
     }
 
@@ -283,10 +1683,84 @@ impl cosmic::Application for CosmicLauncher {
     #[allow(clippy::too_many_lines)]
     fn update(&mut self, message: Message) -> Command<Self::Message> {
         match message {
-            Message::InputChanged(value) => {
+            Message::InputChanged(mut value) => {
+                // A menu of one of the previous results would overlay the new ones.
+                if let Some(command) = self.close_menu() {
+                    return Command::batch(vec![
+                        command,
+                        self.update(Message::InputChanged(value)),
+                    ]);
+                }
+
+                // The input takes the arguments to launch an app with instead of a query.
+                if self.launch_args.is_some() {
+                    self.input_value = value;
+                    return Command::none();
+                }
+
+                // Key presses reach the subscription only after the input inserted their text,
+                // so the label is taken back out of the query here instead.
+                if std::mem::take(&mut self.leader_pending) {
+                    let typed = value
+                        .strip_prefix(self.input_value.as_str())
+                        .filter(|typed| typed.chars().count() == 1);
+                    if let Some(i) = typed.and_then(|typed| LETTER_LABELS.find(typed)) {
+                        return self.update(Message::Activate(Some(i)));
+                    }
+                    // Anything else ends the sequence without being typed.
+                    return Command::none();
+                }
+
+                // The same goes for bare numbers.
+                if self.config.bare_number_activation && self.input_value.is_empty() {
+                    let digit = value.chars().next().and_then(|c| c.to_digit(10));
+                    if let Some(digit) = digit.filter(|_| value.chars().count() == 1) {
+                        return self.update(Message::Activate(Some(digit_activation(digit))));
+                    }
+                }
+
+                let max_len = self.config.max_input_length;
+                self.input_truncated = value.chars().count() > max_len;
+                if self.input_truncated {
+                    value = value.chars().take(max_len).collect();
+                }
                 self.input_value = value.clone();
-                if let Some(tx) = &self.tx {
-                    let _res = tx.blocking_send(launcher::Request::Search(value));
+                self.show_recents = false;
+                self.show_all = false;
+                self.feedback = None;
+                self.confirm_clear_history = false;
+                if value.is_empty() {
+                    self.cursor_at_end = true;
+                }
+
+                let prefix = &self.config.emoji_prefix;
+                if let Some(query) = value.strip_prefix(prefix.as_str()) {
+                    if !prefix.is_empty() {
+                        self.show_emojis(query);
+                        return Command::none();
+                    }
+                }
+                self.emojis = None;
+
+                self.local_filter(&value);
+
+                // iced doesn't tell committed text apart from an input method's preedit, so
+                // searches are debounced to avoid querying every intermediate composition.
+                self.search_generation = self.search_generation.wrapping_add(1);
+                let generation = self.search_generation;
+                // The results of the previous query would linger until the timer fires.
+                if value.is_empty() || self.config.search_debounce == 0 {
+                    return self.update(Message::SearchDebounced(generation));
+                }
+                let debounce = Duration::from_millis(self.config.search_debounce);
+                return Command::perform(tokio::time::sleep(debounce), move |()| {
+                    cosmic::app::message::app(Message::SearchDebounced(generation))
+                });
+            }
+            Message::SearchDebounced(generation) => {
+                if generation == self.search_generation {
+                    self.search_sent = Some(Instant::now());
+                    self.send(launcher::Request::Search(self.input_value.clone()));
                 }
             }
             Message::Backspace => {
@@ -294,16 +1768,32 @@ impl cosmic::Application for CosmicLauncher {
                 if len > 0 {
                     self.input_value.remove(len - 1);
                 }
-                if let Some(tx) = &self.tx {
-                    let _res =
-                        tx.blocking_send(launcher::Request::Search(self.input_value.clone()));
-                }
+                return self.update(Message::InputChanged(self.input_value.clone()));
+            }
+            Message::DeleteWord => {
+                delete_last_word(&mut self.input_value);
+                return self.update(Message::InputChanged(self.input_value.clone()));
+            }
+            Message::TabPress
+                if self
+                    .config
+                    .action(&Key::Named(Named::Tab), Modifiers::empty())
+                    == Some(Action::Activate) =>
+            {
+                return self.update(Message::Activate(None));
             }
             Message::TabPress if !self.alt_tab => {
                 let focused = self.focused;
                 self.focused = 0;
+                // Rows past the first ten, shown once all results are expanded, have no fixed id.
+                let Some(id) = RESULT_IDS.get(focused) else {
+                    if let Some(id) = self.visible_item(focused).map(|item| item.id) {
+                        self.send(launcher::Request::Complete(id));
+                    }
+                    return Command::none();
+                };
                 return command::message(cosmic::app::Message::App(
-                    Self::Message::CompleteFocusedId(RESULT_IDS[focused].clone()),
+                    Self::Message::CompleteFocusedId(id.clone()),
                 ));
             }
             Message::TabPress => {}
@@ -313,60 +1803,407 @@ impl cosmic::Application for CosmicLauncher {
                     .position(|res_id| res_id == &id)
                     .unwrap_or_default();
 
-                if let Some(id) = self.launcher_items.get(i).map(|res| res.id) {
-                    if let Some(tx) = &self.tx {
-                        let _res = tx.blocking_send(launcher::Request::Complete(id));
-                    }
+                if let Some(id) = self
+                    .visible_item(i)
+                    .map(|res| res.id)
+                    .filter(|id| !self.synthetic.contains_key(id))
+                {
+                    self.send(launcher::Request::Complete(id));
                 }
             }
             Message::Activate(i) => {
-                if let (Some(tx), Some(item)) =
-                    (&self.tx, self.launcher_items.get(i.unwrap_or(self.focused)))
+                let item = self.visible_item(i.unwrap_or(self.focused));
+                if self.config.activation_flash && self.config.animate_results {
+                    self.flashing = item.map(|item| item.id);
+                }
+                if let Some(event) = self
+                    .config
+                    .activation_sound
+                    .as_deref()
+                    .filter(|_| item.is_some())
+                {
+                    play_sound(event);
+                }
+                if let Some(emoji) = item.and_then(|item| self.emoji(item)) {
+                    return Command::batch(vec![
+                        iced::clipboard::write(emoji.glyph.to_string()),
+                        self.finish_activation(),
+                    ]);
+                }
+                let synthetic = item
+                    .and_then(|item| self.synthetic.get(&item.id))
+                    .map(|(result, _)| result.action.clone());
+                let command = match synthetic {
+                    Some(SyntheticAction::Copy(text)) => {
+                        return Command::batch(vec![
+                            iced::clipboard::write(text),
+                            self.finish_activation(),
+                        ]);
+                    }
+                    Some(SyntheticAction::Run(command)) => Some(command),
+                    Some(SyntheticAction::OpenUrl(url)) => shlex::try_quote(&url)
+                        .ok()
+                        .map(|url| format!("xdg-open {url}")),
+                    Some(SyntheticAction::Launcher(action)) => {
+                        self.flashing = None;
+                        return self.update(Message::Action(action));
+                    }
+                    None => None,
+                };
+                if let Some(command) = command {
+                    return Command::perform(
+                        cosmic::desktop::spawn_desktop_exec(
+                            command,
+                            Vec::<(String, String)>::new(),
+                            None,
+                        ),
+                        |()| cosmic::app::message::app(Message::Activated),
+                    );
+                }
+
+                if let Some((rule, item)) = self.copy_rule().zip(item) {
+                    let contents = match rule.field {
+                        CopyField::Name => item.name.clone(),
+                        CopyField::Description => item.description.clone(),
+                    };
+                    let paste = rule.paste && in_path(TYPE_COMMAND);
+                    if rule.paste && !paste {
+                        tracing::warn!("{TYPE_COMMAND} isn't installed, only copying the result");
+                    }
+                    let mut commands = vec![iced::clipboard::write(contents)];
+                    if paste {
+                        // The app to paste into must have the focus back, even in sticky mode.
+                        commands.push(self.hide());
+                        commands.push(Command::perform(
+                            wtype(["-M", "ctrl", "v", "-m", "ctrl"].map(String::from).to_vec()),
+                            |()| cosmic::app::message::none(),
+                        ));
+                    } else {
+                        commands.push(self.finish_activation());
+                    }
+                    if let Some(secs) = rule.clear_after {
+                        commands.push(Command::perform(
+                            tokio::time::sleep(Duration::from_secs(secs)),
+                            |()| cosmic::app::message::app(Message::ClearClipboard),
+                        ));
+                    }
+                    return Command::batch(commands);
+                }
+
+                if let Some((id, name, window)) = self
+                    .visible_item(i.unwrap_or(self.focused))
+                    .map(|item| (item.id, item.name.clone(), item.window))
                 {
-                    let _res = tx.blocking_send(launcher::Request::Activate(item.id));
+                    if let Some(window) = window {
+                        self.window_focus.retain(|w| *w != window);
+                        self.window_focus.insert(0, window);
+                        self.window_focus.truncate(WINDOW_FOCUS_HISTORY);
+                    }
+                    let query = self.input_value.trim();
+                    if self.config.learn_selections && !query.is_empty() {
+                        self.usage.selections.insert(query.to_string(), name);
+                        self.usage.save();
+                    }
+                    if self.config.search_history && !query.is_empty() {
+                        self.history = SearchHistory::load();
+                        self.history.push(query);
+                        self.history.save();
+                    }
+                    // Window results are activated by pop-launcher's toplevel plugin through
+                    // the compositor's toplevel management protocol, which also switches to the
+                    // workspace of the window, so they need nothing beyond the result id.
+                    self.send(launcher::Request::Activate(id));
                 } else {
                     return self.hide();
                 }
             }
             #[allow(clippy::cast_possible_wrap)]
             Message::Context(i) => {
-                if self.menu.take().is_some() {
-                    return commands::popup::destroy_popup(*MENU_ID);
+                if let Some(command) = self.close_menu() {
+                    return command;
                 }
 
-                if let (Some(tx), Some(item)) = (&self.tx, self.launcher_items.get(i)) {
-                    let _res = tx.blocking_send(launcher::Request::Context(item.id));
+                if let Some(id) = self
+                    .visible_item(i)
+                    .map(|item| item.id)
+                    .filter(|id| !self.synthetic.contains_key(id))
+                {
+                    self.send(launcher::Request::Context(id));
                 }
             }
+            Message::AnimationTick => {
+                self.appeared
+                    .retain(|_, appeared| appeared.elapsed() < FADE_IN_DURATION);
+            }
+            Message::RetryRequests => self.flush_requests(),
+            Message::DesktopEntries(entries) => {
+                self.desktop_entries = Some(
+                    entries
+                        .into_iter()
+                        .map(|entry| (entry.id.to_string(), entry))
+                        .collect(),
+                );
+                self.resolved_entries.clear();
+                self.desktop_paths.clear();
+                if self.needs_entries() {
+                    let items = self.launcher_items.clone();
+                    self.resolve_entries(&items);
+                }
+            }
+            Message::ClearClipboard => {
+                return iced::clipboard::write(String::new());
+            }
             Message::CursorMoved(pos) => {
                 self.cursor_position = Some(pos);
             }
-            Message::MenuButton(i, context) => {
-                if let Some(tx) = &self.tx {
-                    let _res = tx.blocking_send(launcher::Request::ActivateContext(i, context));
+            Message::Hovered(i) => {
+                self.hovered = Some(i);
+                if self.config.show_preview {
+                    let delay = Duration::from_millis(self.config.hover_preview_delay);
+                    return Command::perform(tokio::time::sleep(delay), move |()| {
+                        cosmic::app::message::app(Message::HoverDelayElapsed(i))
+                    });
+                }
+            }
+            Message::HoverDelayElapsed(i) => {
+                if self.hovered == Some(i) {
+                    self.hover_preview = Some(i);
+                }
+            }
+            Message::Unhovered(i) => {
+                if self.hovered == Some(i) {
+                    self.hovered = None;
+                    self.hover_preview = None;
+                }
+            }
+            Message::ShowAll => {
+                self.show_all = true;
+                let more = std::mem::take(&mut self.more_results);
+                self.launcher_items.extend(more);
+            }
+            Message::ToggleKind(kind) => {
+                if !self.kind_filter.remove(&kind) {
+                    self.kind_filter.insert(kind);
                 }
+                self.focused = 0;
+            }
+            Message::ToggleGroup(name) => {
+                if !self.expanded_groups.remove(&name) {
+                    self.expanded_groups.insert(name);
+                }
+                self.focused = 0;
+            }
+            Message::LaunchFavorite(i) => {
+                let Some(path) = self.favorites.get(i).and_then(|entry| entry.path.clone()) else {
+                    return Command::none();
+                };
+                return activate_desktop_entry(
+                    path,
+                    GpuPreference::Default,
+                    None,
+                    None,
+                    &self.config.terminal,
+                );
+            }
+            Message::ResultsScrolled(offset) => {
+                self.results_offset = offset;
+            }
+            Message::MenuFilterChanged(filter) => {
+                self.menu_filter = filter;
+                self.menu_selected = None;
+            }
+            Message::StartDrag(i) => {
+                if let Some(path) = self.visible_item(i).and_then(file_path) {
+                    return commands::data_device::start_drag(
+                        vec![URI_LIST_MIME.to_string()],
+                        sctk::reexports::client::protocol::wl_data_device_manager::DndAction::Copy,
+                        *WINDOW_ID,
+                        None,
+                        Box::new(FileDrag(path)),
+                    );
+                }
+            }
+            Message::MenuButton(i, option) if self.needs_confirmation(i, option) => {
+                self.confirm_option = Some((i, option));
+            }
+            Message::CancelConfirmation => {
+                self.confirm_option = None;
+            }
+            Message::MenuButton(i, COPY_DESKTOP_PATH_OPTION) => {
+                let path = self
+                    .launcher_items
+                    .iter()
+                    .find(|item| item.id == i)
+                    .and_then(|item| self.desktop_paths.get(&item.name))
+                    .cloned();
+                let mut commands: Vec<_> = self.close_menu().into_iter().collect();
+                if let Some(path) = path {
+                    commands.push(iced::clipboard::write(path.display().to_string()));
+                }
+                return Command::batch(commands);
+            }
+            Message::MenuButton(i, COPY_LAUNCH_COMMAND_OPTION) => {
+                let exec = self
+                    .launcher_items
+                    .iter()
+                    .find(|item| item.id == i)
+                    .and_then(|item| self.desktop_paths.get(&item.name))
+                    .and_then(|path| desktop_entry_value(path, "Exec"));
+                let mut commands: Vec<_> = self.close_menu().into_iter().collect();
+                if let Some(exec) = exec {
+                    commands.push(iced::clipboard::write(strip_field_codes(&exec)));
+                }
+                return Command::batch(commands);
+            }
+            Message::MenuButton(i, LAUNCH_WITH_ARGS_OPTION) => {
+                let item = self.launcher_items.iter().find(|item| item.id == i);
+                if let Some((name, path)) = item.and_then(|item| {
+                    let path = self.desktop_paths.get(&item.name)?;
+                    Some((item.name.clone(), path.clone()))
+                }) {
+                    let query = std::mem::take(&mut self.input_value);
+                    self.launch_args = Some((name, path, query));
+                    self.cursor_at_end = true;
+                }
+                let mut commands: Vec<_> = self.close_menu().into_iter().collect();
+                commands.push(text_input::focus(INPUT_ID.clone()));
+                return Command::batch(commands);
+            }
+            Message::MenuButton(i, TOGGLE_AUTOSTART_OPTION) => {
+                let item = self.launcher_items.iter().find(|item| item.id == i);
+                if let Some((name, path)) = item.and_then(|item| {
+                    let path = self.desktop_paths.get(&item.name)?;
+                    Some((item.name.clone(), path.clone()))
+                }) {
+                    self.feedback = Some(toggle_autostart(&name, &path));
+                }
+                if let Some(command) = self.close_menu() {
+                    return command;
+                }
+            }
+            Message::MenuButton(i, OPEN_ON_NEW_WORKSPACE_OPTION) => {
+                let target = self
+                    .launcher_items
+                    .iter()
+                    .find(|item| item.id == i)
+                    .map(|item| match item.window {
+                        Some(_) => workspaces::Target::Window(item.name.clone()),
+                        None => workspaces::Target::App,
+                    });
+                let index = self.visible_items().position(|item| item.id == i);
+                let mut commands: Vec<_> = self.close_menu().into_iter().collect();
+                if let Some((target, index)) = target.zip(index) {
+                    if let Some(workspaces) = &self.workspaces {
+                        workspaces.open_on_new_workspace(target);
+                    }
+                    commands.push(self.update(Message::Activate(Some(index))));
+                }
+                return Command::batch(commands);
+            }
+            Message::MenuButton(i, context) if self.custom_action(context).is_some() => {
+                let item = self.launcher_items.iter().find(|item| item.id == i);
+                let command = self
+                    .custom_action(context)
+                    .zip(item)
+                    .map(|(action, item)| action.command_for(&self.input_value, &item.name));
+                let mut commands: Vec<_> = self.close_menu().into_iter().collect();
+                if let Some(command) = command {
+                    commands.push(Command::perform(
+                        cosmic::desktop::spawn_desktop_exec(
+                            command,
+                            Vec::<(String, String)>::new(),
+                            None,
+                        ),
+                        |()| cosmic::app::message::app(Message::Activated),
+                    ));
+                }
+                return Command::batch(commands);
+            }
+            Message::MenuButton(i, context) => {
+                self.send(launcher::Request::ActivateContext(i, context));
 
-                if self.menu.take().is_some() {
-                    return commands::popup::destroy_popup(*MENU_ID);
+                if let Some(command) = self.close_menu() {
+                    return command;
                 }
             }
             Message::LauncherEvent(e) => match e {
                 launcher::Event::Started(tx) => {
-                    _ = tx.blocking_send(launcher::Request::Search(String::new()));
+                    self.pending_requests
+                        .push_front(launcher::Request::Search(String::new()));
                     self.tx.replace(tx);
+                    self.flush_requests();
+                    self.warned_backend_unavailable = false;
                 }
                 launcher::Event::Response(response) => match response {
-                    pop_launcher::Response::Close => return self.hide(),
+                    pop_launcher::Response::Close => return self.finish_activation(),
                     #[allow(clippy::cast_possible_truncation)]
-                    pop_launcher::Response::Context { id, options } => {
+                    pop_launcher::Response::Context { id, mut options } => {
+                        let window = self
+                            .launcher_items
+                            .iter()
+                            .any(|item| item.id == id && item.window.is_some());
+                        let desktop_path = self.desktop_path(id);
+                        if self.workspaces.is_some() && (window || desktop_path.is_some()) {
+                            options.push(ContextOption {
+                                id: OPEN_ON_NEW_WORKSPACE_OPTION,
+                                name: fl!("open-on-new-workspace"),
+                            });
+                        }
+                        if let Some(path) = desktop_path {
+                            options.push(ContextOption {
+                                id: COPY_DESKTOP_PATH_OPTION,
+                                name: fl!("copy-desktop-path"),
+                            });
+                            if desktop_entry_value(&path, "Exec").is_some() {
+                                options.push(ContextOption {
+                                    id: COPY_LAUNCH_COMMAND_OPTION,
+                                    name: fl!("copy-launch-command"),
+                                });
+                                options.push(ContextOption {
+                                    id: LAUNCH_WITH_ARGS_OPTION,
+                                    name: fl!("launch-with-arguments"),
+                                });
+                            }
+                            let autostarted = autostart_path(&path).map_or(false, |p| p.exists());
+                            options.push(ContextOption {
+                                id: TOGGLE_AUTOSTART_OPTION,
+                                name: if autostarted {
+                                    fl!("remove-from-startup")
+                                } else {
+                                    fl!("add-to-startup")
+                                },
+                            });
+                        }
+                        let kind = self
+                            .launcher_items
+                            .iter()
+                            .find(|item| item.id == id)
+                            .and_then(|item| self.kind_of(item));
+                        options.extend(
+                            (0..=CUSTOM_ACTION_OPTIONS)
+                                .rev()
+                                .zip(&self.config.custom_actions)
+                                .filter(|(_, action)| action.applies_to(kind))
+                                .map(|(id, action)| ContextOption {
+                                    id,
+                                    name: action.name.clone(),
+                                }),
+                        );
                         if options.is_empty() {
                             return Command::none();
                         }
 
+                        let limits = self.config.context_menu;
+                        let width = menu_width(&options)
+                            .max(limits.min_width)
+                            .min(limits.max_width);
                         self.menu = Some((id, options));
-                        let Some(pos) = self.cursor_position.as_ref() else {
-                            return Command::none();
-                        };
+                        self.confirm_option = None;
+                        self.menu_selected = None;
+                        self.menu_filter.clear();
+                        // The menu may be opened from the keyboard before the cursor ever
+                        // entered the surface.
+                        let pos = self.cursor_position.unwrap_or(Point::ORIGIN);
                         let rect = Rectangle {
                             x: pos.x.round() as i32,
                             y: pos.y.round() as i32,
@@ -379,7 +2216,11 @@ impl cosmic::Application for CosmicLauncher {
                             id: *MENU_ID,
                             positioner: SctkPositioner {
                                 size: None,
-                                size_limits: Limits::NONE.min_width(1.0).min_height(1.0).max_width(300.0).max_height(800.0),
+                                size_limits: Limits::NONE
+                                    .min_width(width)
+                                    .min_height(1.0)
+                                    .max_width(limits.max_width)
+                                    .max_height(limits.max_height),
                                 anchor_rect: rect,
                                 anchor:
                                     sctk::reexports::protocols::xdg::shell::client::xdg_positioner::Anchor::Right,
@@ -396,97 +2237,239 @@ impl cosmic::Application for CosmicLauncher {
                         gpu_preference,
                         action_name,
                     } => {
-                        if let Some(entry) = cosmic::desktop::load_desktop_file(None, path) {
-                            let exec = if let Some(action_name) = action_name {
-                                entry
-                                    .desktop_actions
-                                    .into_iter()
-                                    .find(|action| action.name == action_name)
-                                    .map(|action| action.exec)
-                            } else {
-                                entry.exec
-                            };
-
-                            let Some(exec) = exec else {
-                                return Command::none();
-                            };
-
-                            return request_token(
-                                Some(String::from(Self::APP_ID)),
-                                Some(*WINDOW_ID),
-                                move |token| {
-                                    cosmic::app::Message::App(Message::ActivationToken(
-                                        token,
-                                        entry.id.to_string(),
-                                        exec,
-                                        gpu_preference,
-                                    ))
-                                },
-                            );
-                        }
+                        return activate_desktop_entry(
+                            path,
+                            gpu_preference,
+                            action_name,
+                            None,
+                            &self.config.terminal,
+                        );
                     }
+                    // Results of the emoji picker are provided locally.
+                    pop_launcher::Response::Update(_) if self.emojis.is_some() => {}
                     pop_launcher::Response::Update(mut list) => {
+                        // pop-launcher coalesces searches, so this answers the latest one.
+                        if let Some(sent) = self.search_sent.take() {
+                            self.search_latency = Some(sent.elapsed());
+                        }
                         if self.alt_tab && self.wait_for_result && list.is_empty() {
                             return self.hide();
                         }
+                        if self.needs_entries() {
+                            self.resolve_entries(&list);
+                        }
                         list.sort_by(|a, b| {
-                            let a = i32::from(a.window.is_none());
-                            let b = i32::from(b.window.is_none());
-                            a.cmp(&b)
+                            let windows_first = i32::from(a.window.is_none())
+                                .cmp(&i32::from(b.window.is_none()))
+                                .then_with(|| self.focus_rank(a).cmp(&self.focus_rank(b)));
+                            // The sort is stable, so results keep the order of the backend
+                            // where the focus and launch histories don't tell them apart.
+                            if self.config.frecency_ranking {
+                                windows_first
+                                    .then_with(|| self.frecency(b).total_cmp(&self.frecency(a)))
+                            } else {
+                                windows_first
+                            }
                         });
-                        list.truncate(10);
+                        if self.config.deduplicate_results {
+                            deduplicate(&mut list);
+                        }
+                        if self.config.learn_selections {
+                            let learned = self.usage.selections.get(self.input_value.trim());
+                            if let Some(i) = learned
+                                .and_then(|name| list.iter().position(|item| item.name == *name))
+                            {
+                                let item = list.remove(i);
+                                list.insert(0, item);
+                            }
+                        }
+                        let (mut above, below) = self.synthetic_results();
+                        above.append(&mut list);
+                        list = above;
+                        self.more_results = if self.show_all {
+                            Vec::new()
+                        } else {
+                            list.split_off(list.len().min(self.result_limit()))
+                        };
+                        // Kept past the limit, like a footer of the results.
+                        list.extend(below);
+                        if self.config.animate_results {
+                            let now = Instant::now();
+                            let previous: HashSet<u32> =
+                                self.launcher_items.iter().map(|item| item.id).collect();
+                            self.appeared
+                                .retain(|id, _| list.iter().any(|item| item.id == *id));
+                            for item in list.iter().filter(|item| !previous.contains(&item.id)) {
+                                self.appeared.insert(item.id, now);
+                            }
+                        }
+                        self.file_previews = if self.config.show_preview {
+                            list.iter()
+                                .chain(&self.more_results)
+                                .filter_map(|item| Some((item.id, FilePreview::of(item)?)))
+                                .collect()
+                        } else {
+                            HashMap::new()
+                        };
                         self.launcher_items.splice(.., list);
+                        if self.config.show_recently_used && self.recent_files.is_none() {
+                            self.recent_files = Some(RecentFiles::load());
+                        }
+                        self.local_query = None;
 
+                        let anchor = self.layer_anchor();
                         if self.wait_for_result {
                             self.wait_for_result = false;
-                            return Command::batch(vec![get_layer_surface(
-                                SctkLayerSurfaceSettings {
-                                    id: *WINDOW_ID,
-                                    keyboard_interactivity: KeyboardInteractivity::Exclusive,
-                                    anchor: Anchor::TOP,
-                                    namespace: "launcher".into(),
-                                    size: None,
-                                    margin: iced::wayland::actions::layer_surface::IcedMargin {
-                                        top: 16,
-                                        ..Default::default()
-                                    },
-                                    size_limits: Limits::NONE
-                                        .min_width(1.0)
-                                        .min_height(1.0)
-                                        .max_width(600.0),
+                            self.anchor = anchor;
+                            let mut commands = Vec::new();
+                            // Created first, so that it is stacked below the window.
+                            if self.config.click_outside_backdrop {
+                                self.backdrop = true;
+                                commands.push(get_layer_surface(SctkLayerSurfaceSettings {
+                                    id: *BACKDROP_ID,
+                                    keyboard_interactivity: KeyboardInteractivity::None,
+                                    anchor: Anchor::all(),
+                                    output: IcedOutput::Active,
+                                    layer: self.config.layer.into(),
+                                    namespace: "launcher-backdrop".into(),
+                                    size: Some((None, None)),
+                                    exclusive_zone: -1,
                                     ..Default::default()
+                                }));
+                            }
+                            let keyboard_interactivity = if self.config.grab_keyboard {
+                                KeyboardInteractivity::Exclusive
+                            } else {
+                                KeyboardInteractivity::None
+                            };
+                            if self.surface_created {
+                                commands.extend([
+                                    set_layer(*WINDOW_ID, self.config.layer.into()),
+                                    set_anchor(*WINDOW_ID, anchor),
+                                    set_size(*WINDOW_ID, None, None),
+                                    set_keyboard_interactivity(*WINDOW_ID, keyboard_interactivity),
+                                ]);
+                                commands.extend(self.select_prefilled());
+                                return Command::batch(commands);
+                            }
+                            self.surface_created = true;
+                            commands.push(get_layer_surface(SctkLayerSurfaceSettings {
+                                id: *WINDOW_ID,
+                                keyboard_interactivity,
+                                anchor,
+                                layer: self.config.layer.into(),
+                                namespace: "launcher".into(),
+                                size: None,
+                                margin: if self.fullscreen() {
+                                    Margin::default().into()
+                                } else {
+                                    self.margin().into()
                                 },
-                            )]);
+                                size_limits: Limits::NONE
+                                    .min_width(1.0)
+                                    .min_height(1.0)
+                                    .max_width(self.max_width()),
+                                ..Default::default()
+                            }));
+                            commands.extend(self.select_prefilled());
+                            return Command::batch(commands);
+                        }
+
+                        if self.active_surface && anchor != self.anchor {
+                            self.anchor = anchor;
+                            return set_anchor(*WINDOW_ID, anchor);
                         }
                     }
                     pop_launcher::Response::Fill(s) => {
+                        // The activation completed the query instead of launching something.
+                        self.flashing = None;
                         self.input_value = s;
-                        if let Some(tx) = &self.tx {
-                            let _res = tx
-                                .blocking_send(launcher::Request::Search(self.input_value.clone()));
+                        self.cursor_at_end = true;
+                        self.send(launcher::Request::Search(self.input_value.clone()));
+                        if let Some(command) = self.select_prefilled() {
+                            return command;
                         }
                     }
                 },
             },
             Message::Layer(e) => match e {
-                LayerEvent::Focused => {}
-                LayerEvent::Unfocused => {
+                // A surface kept while hidden loses the focus once more as it is hidden.
+                LayerEvent::Unfocused if self.active_surface => {
                     self.last_hide = Instant::now();
                     return self.hide();
                 }
-                LayerEvent::Done => {}
+                LayerEvent::Focused | LayerEvent::Unfocused | LayerEvent::Done => {}
+            },
+            Message::Output(event, output) => match event {
+                OutputEvent::Created(Some(info)) | OutputEvent::InfoUpdate(info) => {
+                    if let Some(size) = info.logical_size {
+                        self.outputs.insert(output, size);
+                    }
+                }
+                OutputEvent::Created(None) => {}
+                OutputEvent::Removed => {
+                    self.outputs.remove(&output);
+                }
             },
             Message::CloseContextMenu => {
-                if self.menu.take().is_some() {
-                    return commands::popup::destroy_popup(*MENU_ID);
+                if let Some(command) = self.close_menu() {
+                    return command;
+                }
+            }
+            Message::Activated => {
+                return self.finish_activation();
+            }
+            Message::FlashDone => {
+                // The launcher may have been hidden by other means meanwhile.
+                if self.flashing.take().is_some() {
+                    return self.hide();
                 }
             }
             Message::Hide => {
-                if self.menu.take().is_some() {
-                    return commands::popup::destroy_popup(*MENU_ID);
+                // Escape dismisses an open context menu exactly like a click outside of it,
+                // and only hides the launcher once no menu is open.
+                if let Some(command) = self.close_menu() {
+                    return command;
                 }
                 return self.hide();
             }
+            Message::Escape => {
+                if self.menu.is_some() {
+                    return self.update(Message::Hide);
+                }
+                // Escape leaves the arguments of an app for the query entered before.
+                if let Some((_, _, query)) = self.launch_args.take() {
+                    self.input_value = query;
+                    return text_input::move_cursor_to_end(INPUT_ID.clone());
+                }
+                let repeated = self
+                    .last_escape
+                    .take()
+                    .map_or(false, |last| last.elapsed() < DOUBLE_ESCAPE_INTERVAL);
+                if self.sticky && self.config.sticky_double_escape && !repeated {
+                    self.last_escape = Some(Instant::now());
+                    if !self.input_value.is_empty() {
+                        self.input_value.clear();
+                        self.local_query = None;
+                        self.focused = 0;
+                        self.send(launcher::Request::Search(String::new()));
+                    }
+                    return Command::none();
+                }
+                return self.update(Message::Hide);
+            }
+            Message::KeyboardNav(e) if self.menu.is_some() && self.confirm_option.is_none() => {
+                match e {
+                    keyboard_nav::Message::FocusNext => return self.move_menu_selection(true),
+                    keyboard_nav::Message::FocusPrevious => return self.move_menu_selection(false),
+                    _ => {}
+                }
+            }
+            Message::KeyboardNav(
+                e @ (keyboard_nav::Message::FocusNext | keyboard_nav::Message::FocusPrevious),
+            ) if self.browsing_history() => {
+                return self.recall_query(matches!(e, keyboard_nav::Message::FocusPrevious));
+            }
             Message::KeyboardNav(e) => {
                 match e {
                     keyboard_nav::Message::FocusNext => {
@@ -497,17 +2480,26 @@ impl cosmic::Application for CosmicLauncher {
                     }
                     keyboard_nav::Message::Escape => {
                         self.input_value.clear();
-                        if let Some(tx) = &self.tx {
-                            let _res = tx.blocking_send(launcher::Request::Search(String::new()));
-                        }
+                        self.send(launcher::Request::Search(String::new()));
                     }
                     _ => {}
                 };
             }
             Message::ActivationToken(token, app_id, exec, dgpu) => {
-                return Command::perform(launch(token, app_id, exec, dgpu), |()| {
-                    cosmic::app::message::app(Message::Hide)
-                });
+                let last = LastLaunch {
+                    app_id,
+                    exec,
+                    gpu_preference: dgpu,
+                };
+                // The history may have been cleared with the `clear-history` subcommand meanwhile.
+                self.usage = UsageStore::load();
+                self.usage.record(&last.app_id);
+                self.usage.last_launch = Some(last.clone());
+                self.usage.save();
+                return Command::perform(
+                    launch(token, last.app_id, last.exec, last.gpu_preference),
+                    |()| cosmic::app::message::app(Message::Activated),
+                );
             }
             Message::AltTab => {
                 if self.alt_tab {
@@ -521,6 +2513,219 @@ impl cosmic::Application for CosmicLauncher {
                     return self.update(Message::Activate(None));
                 }
             }
+            Message::KeyPressed(key, modifiers) => {
+                // The input inserts the label itself while focused, which ends the sequence.
+                if std::mem::take(&mut self.leader_pending) {
+                    if let Key::Character(c) = &key {
+                        if let Some(i) = LETTER_LABELS
+                            .find(c.as_str())
+                            .filter(|_| modifiers.is_empty())
+                        {
+                            return self.update(Message::Activate(Some(i)));
+                        }
+                    }
+                }
+
+                if modifiers.is_empty() {
+                    if let Some(command) = self.input_cursor_key(&key) {
+                        return command;
+                    }
+                }
+
+                if let Some(action) = self.config.action(&key, modifiers) {
+                    return self.update(Message::Action(action));
+                }
+            }
+            // The key went into the query, so activating the result as well would conflict with
+            // typing it.
+            Message::KeyTyped(key, modifiers)
+                if self.config.action(&key, modifiers) == Some(Action::Activate) => {}
+            Message::KeyTyped(key, modifiers) => {
+                return self.update(Message::KeyPressed(key, modifiers));
+            }
+            Message::Action(action) => match action {
+                Action::Activate if self.launch_args.is_some() => {
+                    let Some((_, path, _)) = self.launch_args.take() else {
+                        return Command::none();
+                    };
+                    let args = std::mem::take(&mut self.input_value);
+                    return activate_desktop_entry(
+                        path,
+                        GpuPreference::Default,
+                        None,
+                        Some(&args),
+                        &self.config.terminal,
+                    );
+                }
+                Action::Activate if self.menu.is_none() => {
+                    return self.update(Message::Activate(None));
+                }
+                // The filter field of long menus submits on Enter by itself.
+                Action::Activate if !self.menu_filter_shown() => {
+                    let option = self
+                        .menu_selected
+                        .and_then(|i| self.visible_menu_options().get(i).map(|o| o.id));
+                    if let Some((id, option)) = self.menu.as_ref().map(|(id, _)| *id).zip(option) {
+                        return self.update(Message::MenuButton(id, option));
+                    }
+                }
+                Action::ContextMenu if self.config.context_menus_enabled => {
+                    return self.update(Message::Context(self.focused));
+                }
+                Action::Launch(desktop_id) => {
+                    let Some(path) = find_desktop_entry(&desktop_id) else {
+                        tracing::warn!("no desktop entry found for shortcut: {desktop_id}");
+                        return Command::none();
+                    };
+                    return activate_desktop_entry(
+                        path,
+                        GpuPreference::Default,
+                        None,
+                        None,
+                        &self.config.terminal,
+                    );
+                }
+                Action::RepeatLast if self.input_value.is_empty() => {
+                    let Some(last) = self.usage.last_launch.clone() else {
+                        return Command::none();
+                    };
+                    if find_desktop_entry(&last.app_id).is_none() {
+                        tracing::warn!("last launched app no longer exists: {}", last.app_id);
+                        return Command::none();
+                    }
+                    return request_launch(last.app_id, last.exec, last.gpu_preference);
+                }
+                Action::ToggleRecents => {
+                    self.show_recents = !self.show_recents;
+                    self.focused = 0;
+                    let query = if self.show_recents {
+                        String::new()
+                    } else {
+                        self.input_value.clone()
+                    };
+                    self.send(launcher::Request::Search(query));
+                }
+                Action::ScrollUp => {
+                    return self.scroll_results(-RESULT_ROW_HEIGHT);
+                }
+                Action::ScrollDown => {
+                    return self.scroll_results(RESULT_ROW_HEIGHT);
+                }
+                Action::TypeResult if self.config.type_results => {
+                    let Some(item) = self.visible_item(self.focused) else {
+                        return Command::none();
+                    };
+                    let text = match (self.emoji(item), self.copy_rule()) {
+                        (Some(emoji), _) => emoji.glyph.to_string(),
+                        (None, Some(rule)) if rule.field == CopyField::Description => {
+                            item.description.clone()
+                        }
+                        (None, _) => item.name.clone(),
+                    };
+                    if !in_path(TYPE_COMMAND) {
+                        self.feedback = Some(fl!("typing-unavailable", command = TYPE_COMMAND));
+                        return Command::none();
+                    }
+                    return Command::batch(vec![
+                        self.hide(),
+                        Command::perform(wtype(vec![String::from("--"), text]), |()| {
+                            cosmic::app::message::none()
+                        }),
+                    ]);
+                }
+                Action::Leader if self.config.letter_labels => {
+                    self.leader_pending = true;
+                }
+                Action::ZoomIn => return self.zoom(UI_SCALE_STEP),
+                Action::ZoomOut => return self.zoom(-UI_SCALE_STEP),
+                Action::ClearHistory if self.confirm_clear_history => {
+                    self.confirm_clear_history = false;
+                    self.usage.clear_launches();
+                    self.history = SearchHistory::default();
+                    self.history_index = None;
+                    self.feedback = Some(match crate::usage::clear() {
+                        Ok(()) => fl!("history-cleared"),
+                        Err(err) => {
+                            tracing::error!("failed to clear the launch and search history: {err}");
+                            fl!("history-clear-failed")
+                        }
+                    });
+                }
+                Action::ClearHistory => {
+                    self.confirm_clear_history = true;
+                    self.feedback = Some(fl!("confirm-clear-history"));
+                }
+                Action::AssignSlot(slot) => {
+                    let Some(item) = self.visible_item(self.focused).cloned() else {
+                        return Command::none();
+                    };
+                    self.resolve_entries(std::slice::from_ref(&item));
+                    let entry = self.resolved_entries.get(&item.name).cloned().flatten();
+                    let Some(entry) = entry.filter(|_| item.window.is_none()) else {
+                        self.feedback = Some(fl!("slot-not-an-app", name = item.name));
+                        return Command::none();
+                    };
+                    self.quick_slots = QuickSlots::load();
+                    self.quick_slots.slots.insert(slot, entry.id);
+                    self.quick_slots.save();
+                    self.feedback = Some(fl!("slot-assigned", name = item.name, slot = slot));
+                }
+                Action::LaunchSlot(slot) => {
+                    let Some(desktop_id) = self.quick_slots.slots.get(&slot) else {
+                        return Command::none();
+                    };
+                    let Some(path) = find_desktop_entry(desktop_id) else {
+                        tracing::warn!("no desktop entry found for quick slot {slot}");
+                        return Command::none();
+                    };
+                    return activate_desktop_entry(
+                        path,
+                        GpuPreference::Default,
+                        None,
+                        None,
+                        &self.config.terminal,
+                    );
+                }
+                Action::ShowAll => {
+                    return self.update(Message::ShowAll);
+                }
+                Action::ToggleSticky => {
+                    self.sticky = !self.sticky;
+                }
+                Action::ToggleHidden => {
+                    self.hidden_apps = match self.hidden_apps {
+                        Some(_) => None,
+                        None => Some(Rc::new(HiddenApps::load())),
+                    };
+                    self.send(launcher::Request::Search(self.input_value.clone()));
+                }
+                Action::Activate
+                | Action::ContextMenu
+                | Action::RepeatLast
+                | Action::TypeResult
+                | Action::Leader => {}
+            },
+            // Styles read the theme while rendering, so handling the change is enough to redraw
+            // with the new colors. Anything cached from the theme must be invalidated here.
+            Message::ThemeChanged => {}
+            Message::Config(config) => {
+                if config.ui_scale != self.config.ui_scale {
+                    self.core.set_scale_factor(config.ui_scale());
+                }
+                if config.sticky != self.config.sticky {
+                    self.sticky = config.sticky;
+                }
+                if config.favorites != self.config.favorites {
+                    self.favorites = load_favorites(&config.favorites);
+                }
+                if config.unit_conversion != self.config.unit_conversion
+                    || config.exchange_rates_url != self.config.exchange_rates_url
+                {
+                    self.converter = providers::converter(&config);
+                }
+                self.providers = providers::builtin(&config, self.converter.as_ref());
+                self.config = config;
+            }
         }
         Command::none()
     }
@@ -531,19 +2736,17 @@ impl cosmic::Application for CosmicLauncher {
     ) -> iced::Command<cosmic::app::Message<Self::Message>> {
         match msg.msg {
             DbusActivationDetails::Activate => {
-                if self.active_surface || self.wait_for_result {
+                // Also cancels a surface still waiting for its first results, which would
+                // otherwise never open if pop-launcher doesn't answer.
+                if self.active_surface {
                     return self.hide();
                 } else if self.last_hide.elapsed().as_millis() > 100 {
-                    if let Some(tx) = &self.tx {
-                        let _res = tx.blocking_send(launcher::Request::Search(String::new()));
-                    } else {
-                        tracing::info!("NOT FOUND");
-                    }
+                    self.send(launcher::Request::Search(String::new()));
 
                     self.input_value = String::new();
                     self.active_surface = true;
                     self.wait_for_result = true;
-                    return Command::none();
+                    return load_desktop_entries();
                 }
             }
             DbusActivationDetails::ActivateAction { action, .. } => {
@@ -551,11 +2754,7 @@ impl cosmic::Application for CosmicLauncher {
                     return Command::none();
                 }
 
-                if let Some(tx) = &self.tx {
-                    let _res = tx.blocking_send(launcher::Request::Search(String::new()));
-                } else {
-                    tracing::info!("NOT FOUND");
-                }
+                self.send(launcher::Request::Search(String::new()));
                 if self.active_surface {
                     if self.launcher_items.is_empty() {
                         return cosmic::command::message(cosmic::app::message::app(Message::Hide));
@@ -566,7 +2765,10 @@ impl cosmic::Application for CosmicLauncher {
                 self.input_value = action;
                 self.active_surface = true;
                 self.wait_for_result = true;
-                return cosmic::command::message(cosmic::app::message::app(Message::AltTab));
+                return Command::batch([
+                    load_desktop_entries(),
+                    cosmic::command::message(cosmic::app::message::app(Message::AltTab)),
+                ]);
             }
             DbusActivationDetails::Open { .. } => {}
         }
@@ -579,24 +2781,32 @@ impl cosmic::Application for CosmicLauncher {
 
     #[allow(clippy::too_many_lines)]
     fn view_window(&self, id: SurfaceId) -> Element<Self::Message> {
+        // A kept surface shows nothing while hidden.
+        if id == *WINDOW_ID && !self.active_surface {
+            return horizontal_space(Length::Fixed(1.0)).into();
+        }
         if id == *WINDOW_ID {
-            let launcher_entry = text_input::search_input(fl!("type-to-search"), &self.input_value)
+            let accent = self.config.accent_input_border;
+            let placeholder = match &self.launch_args {
+                Some((name, ..)) => fl!("arguments-for", app = name.as_str()),
+                None => fl!("type-to-search"),
+            };
+            let launcher_entry = text_input::search_input(placeholder, &self.input_value)
                 .on_input(Message::InputChanged)
                 .on_paste(Message::InputChanged)
-                .on_submit(Message::Activate(None))
                 .style(cosmic::theme::TextInput::Custom {
-                    active: Box::new(|theme| theme.focused(&cosmic::theme::TextInput::Search)),
-                    error: Box::new(|theme| theme.focused(&cosmic::theme::TextInput::Search)),
-                    hovered: Box::new(|theme| theme.focused(&cosmic::theme::TextInput::Search)),
-                    focused: Box::new(|theme| theme.focused(&cosmic::theme::TextInput::Search)),
+                    active: Box::new(move |theme| search_input_appearance(theme, accent)),
+                    error: Box::new(move |theme| search_input_appearance(theme, accent)),
+                    hovered: Box::new(move |theme| search_input_appearance(theme, accent)),
+                    focused: Box::new(move |theme| search_input_appearance(theme, accent)),
                     disabled: Box::new(|theme| theme.disabled(&cosmic::theme::TextInput::Search)),
                 })
                 .id(INPUT_ID.clone())
                 .always_active();
 
+            let len = self.visible_items().count();
             let buttons: Vec<_> = self
-                .launcher_items
-                .iter()
+                .visible_items()
                 .enumerate()
                 .flat_map(|(i, item)| {
                     let (name, desc) = if item.window.is_some() {
@@ -605,48 +2815,109 @@ impl cosmic::Application for CosmicLauncher {
                         (&item.name, &item.description)
                     };
 
+                    // pop-launcher leaves the description of some apps empty.
+                    let comment = self
+                        .resolved_entries
+                        .get(&item.name)
+                        .and_then(Option::as_ref)
+                        .and_then(|entry| entry.comment.as_ref())
+                        .filter(|_| item.window.is_none() && desc.is_empty());
+                    let desc = comment.unwrap_or(desc);
+
+                    let alpha = self.fade_alpha(item.id);
+                    let rtl = self.config.text_direction.is_rtl();
+                    let (start, end, text_alignment) = if rtl {
+                        (Horizontal::Right, Horizontal::Left, Alignment::End)
+                    } else {
+                        (Horizontal::Left, Horizontal::Right, Alignment::Start)
+                    };
+                    let query = self.input_value.trim();
                     let name = Column::with_children(name.lines().map(|line| {
-                        text(if line.width() > 45 {
+                        let line = if line.width() > 45 {
                             format!("{}...", line.unicode_truncate(45).0)
                         } else {
                             line.to_string()
-                        })
-                        .horizontal_alignment(Horizontal::Left)
-                        .vertical_alignment(Vertical::Center)
-                        .size(14)
-                        .style(cosmic::theme::Text::Custom(|t| {
-                            cosmic::iced::widget::text::Appearance {
-                                color: Some(t.cosmic().on_bg_color().into()),
-                            }
-                        }))
-                        .into()
-                    }));
+                        };
+                        if self.config.highlight_matches && !query.is_empty() {
+                            return highlighted_line(&line, query, alpha);
+                        }
+                        text(line)
+                            .horizontal_alignment(start)
+                            .vertical_alignment(Vertical::Center)
+                            .size(14)
+                            .style(row_text_style(alpha))
+                            .into()
+                    }))
+                    .align_items(text_alignment);
 
-                    let desc = Column::with_children(desc.lines().map(|line| {
-                        text(if line.width() > 60 {
-                            format!("{}...", line.unicode_truncate(60).0)
-                        } else {
-                            line.to_string()
-                        })
-                        .horizontal_alignment(Horizontal::Left)
-                        .vertical_alignment(Vertical::Center)
-                        .size(10)
-                        .style(theme::Text::Custom(|t| {
-                            cosmic::iced::widget::text::Appearance {
-                                color: Some(t.cosmic().on_bg_color().into()),
+                    let max_lines = self.config.max_desc_lines.unwrap_or(usize::MAX);
+                    let line_count = desc.lines().count();
+                    let desc = Column::with_children(desc.lines().take(max_lines).enumerate().map(
+                        |(n, line)| {
+                            // The last line shown ends with an ellipsis when more lines are cut.
+                            let line = if n + 1 == max_lines && line_count > max_lines {
+                                format!("{line}...")
+                            } else {
+                                line.to_string()
+                            };
+                            if self.config.render_markup {
+                                return markup_line(&line, alpha);
                             }
-                        }))
-                        .into()
-                    }));
+                            text(if line.width() > 60 {
+                                format!("{}...", line.unicode_truncate(60).0)
+                            } else {
+                                line
+                            })
+                            .horizontal_alignment(start)
+                            .vertical_alignment(Vertical::Center)
+                            .size(10)
+                            .style(row_text_style(alpha))
+                            .into()
+                        },
+                    ))
+                    .align_items(text_alignment);
 
                     let mut button_content = Vec::new();
+                    let group_size = self.window_group(item).count();
+                    let group_head = self.window_group(item).next().map(|head| head.id);
+                    if group_size > 1 && group_head == Some(item.id) {
+                        let expanded = self.expanded_groups.contains(&item.name);
+                        button_content.push(
+                            button::icon(from_name(if expanded {
+                                "pan-down-symbolic"
+                            } else {
+                                "pan-end-symbolic"
+                            }))
+                            .on_press(Message::ToggleGroup(item.name.clone()))
+                            .into(),
+                        );
+                    } else if group_size > 1 {
+                        // Indent the windows of an expanded group under its first window.
+                        button_content.push(horizontal_space(Length::Fixed(32.0)).into());
+                    }
+                    if self.config.show_index_numbers {
+                        button_content.push(
+                            text(format!("{}.", i + 1))
+                                .size(14)
+                                .vertical_alignment(Vertical::Center)
+                                .style(theme::Text::Custom(|t| {
+                                    cosmic::iced::widget::text::Appearance {
+                                        color: Some(t.cosmic().on_bg_color().into()),
+                                    }
+                                }))
+                                .into(),
+                        );
+                    }
                     if !self.alt_tab {
                         if let Some(source) = item.category_icon.as_ref() {
                             let name = match source {
                                 IconSource::Name(name) | IconSource::Mime(name) => name,
                             };
+                            let handle = self
+                                .themed_icon(name, 16)
+                                .unwrap_or_else(|| from_name(name.clone()).into());
                             button_content.push(
-                                icon(from_name(name.clone()).into())
+                                icon(handle)
                                     .width(Length::Fixed(16.0))
                                     .height(Length::Fixed(16.0))
                                     .style(cosmic::theme::Svg::Custom(Rc::new(|theme| {
@@ -658,33 +2929,111 @@ impl cosmic::Application for CosmicLauncher {
                             );
                         }
                     }
-                    if let Some(source) = item.icon.as_ref() {
+                    if let Some(emoji) = self.emoji(item) {
+                        button_content.push(text(emoji.glyph).size(32).into());
+                    } else if let Some(source) = item.icon.as_ref() {
                         let name = match source {
                             IconSource::Name(name) | IconSource::Mime(name) => name,
                         };
+                        let handle = self.themed_icon(name, 64).unwrap_or_else(|| {
+                            from_name(name.clone())
+                                .size(64)
+                                .fallback(Some(IconFallback::Names(vec![
+                                    "application-default".into(),
+                                    "application-x-executable".into(),
+                                ])))
+                                .into()
+                        });
                         button_content.push(
-                            icon(
-                                from_name(name.clone())
-                                    .size(64)
-                                    .fallback(Some(IconFallback::Names(vec![
-                                        "application-default".into(),
-                                        "application-x-executable".into(),
-                                    ])))
-                                    .into(),
-                            )
-                            .width(Length::Fixed(32.0))
-                            .height(Length::Fixed(32.0))
-                            .into(),
+                            icon(handle)
+                                .width(Length::Fixed(32.0))
+                                .height(Length::Fixed(32.0))
+                                .into(),
                         );
                     }
 
-                    button_content.push(column![name, desc].width(Length::FillPortion(4)).into());
+                    let mut text_column = column![name, desc].align_items(text_alignment);
+                    let exec = self
+                        .resolved_entries
+                        .get(&item.name)
+                        .and_then(Option::as_ref)
+                        .and_then(|entry| entry.exec.as_deref())
+                        .filter(|_| self.config.show_exec && item.window.is_none());
+                    if let Some(exec) = exec {
+                        text_column = text_column.push(
+                            text(if exec.width() > 60 {
+                                format!("{}...", exec.unicode_truncate(60).0)
+                            } else {
+                                exec.to_string()
+                            })
+                            .size(10)
+                            .style(row_text_style(alpha * 0.6)),
+                        );
+                    }
+                    let last_used = self
+                        .recent_files
+                        .as_ref()
+                        .filter(|_| self.config.show_recently_used)
+                        .zip(file_path(item))
+                        .and_then(|(recent_files, path)| recent_files.last_used(&path));
+                    if let Some(last_used) = last_used {
+                        text_column = text_column.push(
+                            text(recent::used_ago(last_used))
+                                .size(10)
+                                .style(row_text_style(alpha * 0.6)),
+                        );
+                    }
+                    button_content.push(text_column.width(Length::FillPortion(4)).into());
+                    if let Some(source) = self.source(item).filter(|_| self.config.show_source) {
+                        button_content.push(
+                            container(text::caption(source))
+                                .padding([2, 6])
+                                .style(theme::Container::custom(|theme| {
+                                    let cosmic = theme.cosmic();
+                                    ContainerAppearance {
+                                        text_color: Some(cosmic.on_bg_component_color().into()),
+                                        background: Some(
+                                            Color::from(cosmic.bg_component_color()).into(),
+                                        ),
+                                        border: Border {
+                                            radius: cosmic.corner_radii.radius_xs.into(),
+                                            ..Default::default()
+                                        },
+                                        ..Default::default()
+                                    }
+                                }))
+                                .into(),
+                        );
+                    }
+                    let slot = self
+                        .resolved_entries
+                        .get(&item.name)
+                        .and_then(Option::as_ref)
+                        .filter(|_| item.window.is_none())
+                        .and_then(|entry| self.quick_slots.slot_of(&entry.id));
+                    let label = LETTER_LABELS
+                        .chars()
+                        .nth(i)
+                        .filter(|_| self.config.letter_labels);
+                    let shortcut = activation_digit(i)
+                        .map(|digit| format!("Ctrl + {digit}"))
+                        .into_iter()
+                        .chain(slot.map(|slot| format!("Alt + {slot}")))
+                        .chain(label.map(|label| {
+                            if self.leader_pending {
+                                format!("[{label}]")
+                            } else {
+                                label.to_string()
+                            }
+                        }))
+                        .collect::<Vec<_>>()
+                        .join("\n");
                     button_content.push(
                         container(
-                            text(format!("Ctrl + {}", (i + 1) % 10))
+                            text(shortcut)
                                 .size(14)
                                 .vertical_alignment(Vertical::Center)
-                                .horizontal_alignment(Horizontal::Right)
+                                .horizontal_alignment(end)
                                 .style(theme::Text::Custom(|t| {
                                     cosmic::iced::widget::text::Appearance {
                                         color: Some(t.cosmic().on_bg_color().into()),
@@ -694,24 +3043,58 @@ impl cosmic::Application for CosmicLauncher {
                         .width(Length::FillPortion(1))
                         .center_y()
                         .align_y(Vertical::Center)
-                        .align_x(Horizontal::Right)
+                        .align_x(end)
                         .into(),
                     );
+                    // Reserve the space of the action icon so rows don't shift on hover.
+                    button_content.push(if self.hovered == Some(i) {
+                        let name = if rtl {
+                            "go-previous-symbolic"
+                        } else {
+                            "go-next-symbolic"
+                        };
+                        icon(from_name(name).into())
+                            .width(Length::Fixed(16.0))
+                            .height(Length::Fixed(16.0))
+                            .style(cosmic::theme::Svg::Custom(Rc::new(|theme| {
+                                cosmic::iced_style::svg::Appearance {
+                                    color: Some(theme.cosmic().accent_color().into()),
+                                }
+                            })))
+                            .into()
+                    } else {
+                        horizontal_space(Length::Fixed(16.0)).into()
+                    });
+                    if rtl {
+                        button_content.reverse();
+                    }
                     let is_focused = i == self.focused;
-                    let btn = mouse_area(
+                    let is_flashing = self.flashing == Some(item.id);
+                    let fullscreen = self.fullscreen();
+                    let radius = self.config.result_corner_radius;
+                    let hover_radius = self.config.hovered_corner_radius.or(radius);
+                    // Window results show the window title first.
+                    let accessible_name = if item.window.is_some() {
+                        &item.description
+                    } else {
+                        &item.name
+                    };
+                    let mut btn = mouse_area(
                         cosmic::widget::button(
                             row(button_content)
                                 .spacing(8)
                                 .align_items(Alignment::Center),
                         )
-                        .id(RESULT_IDS[i].clone())
+                        .id(RESULT_IDS.get(i).cloned().unwrap_or_else(Id::unique))
+                        .name(accessible_name.clone())
                         .width(Length::Fill)
                         .on_press(Message::Activate(Some(i)))
-                        .padding([8, 24])
+                        .padding(if fullscreen { [16, 24] } else { [8, 24] })
                         .style(Button::Custom {
                             active: Box::new(move |focused, theme| {
                                 let focused = is_focused || focused;
-                                let rad_s = theme.cosmic().corner_radii.radius_s;
+                                let rad_s =
+                                    radius.map_or(theme.cosmic().corner_radii.radius_s, |r| [r; 4]);
                                 let a = if focused {
                                     button::StyleSheet::hovered(
                                         theme,
@@ -727,15 +3110,22 @@ impl cosmic::Application for CosmicLauncher {
                                         &Button::Text,
                                     )
                                 };
-                                button::Appearance {
+                                let mut appearance = button::Appearance {
                                     border_radius: rad_s.into(),
                                     outline_width: 0.0,
                                     ..a
+                                };
+                                if is_flashing {
+                                    let mut color = Color::from(theme.cosmic().accent_color());
+                                    color.a = 0.3;
+                                    appearance.background = Some(color.into());
                                 }
+                                appearance
                             }),
                             hovered: Box::new(move |focused, theme| {
                                 let focused = is_focused || focused;
-                                let rad_s = theme.cosmic().corner_radii.radius_s;
+                                let rad_s = hover_radius
+                                    .map_or(theme.cosmic().corner_radii.radius_s, |r| [r; 4]);
 
                                 let text = button::StyleSheet::hovered(
                                     theme,
@@ -749,8 +3139,9 @@ impl cosmic::Application for CosmicLauncher {
                                     ..text
                                 }
                             }),
-                            disabled: Box::new(|theme| {
-                                let rad_s = theme.cosmic().corner_radii.radius_s;
+                            disabled: Box::new(move |theme| {
+                                let rad_s =
+                                    radius.map_or(theme.cosmic().corner_radii.radius_s, |r| [r; 4]);
 
                                 let text = button::StyleSheet::disabled(theme, &Button::Text);
                                 button::Appearance {
@@ -761,7 +3152,8 @@ impl cosmic::Application for CosmicLauncher {
                             }),
                             pressed: Box::new(move |focused, theme| {
                                 let focused = is_focused || focused;
-                                let rad_s = theme.cosmic().corner_radii.radius_s;
+                                let rad_s = hover_radius
+                                    .map_or(theme.cosmic().corner_radii.radius_s, |r| [r; 4]);
 
                                 let text = button::StyleSheet::pressed(
                                     theme,
@@ -777,8 +3169,13 @@ impl cosmic::Application for CosmicLauncher {
                             }),
                         }),
                     )
-                    .on_right_release(Message::Context(i));
-                    if i == self.launcher_items.len() - 1 {
+                    .on_mouse_enter(Message::Hovered(i))
+                    .on_mouse_exit(Message::Unhovered(i))
+                    .on_drag(Message::StartDrag(i));
+                    if self.config.context_menus_enabled {
+                        btn = btn.on_right_release(Message::Context(i));
+                    }
+                    if i == len - 1 {
                         vec![btn.into()]
                     } else {
                         vec![btn.into(), divider::horizontal::light().into()]
@@ -786,16 +3183,115 @@ impl cosmic::Application for CosmicLauncher {
                 })
                 .collect();
 
+            let spacing = self.config.content_spacing;
+            let width = self.config.window_width();
             let mut content = if self.alt_tab {
-                Column::new().max_width(600).spacing(16)
+                Column::new().max_width(width).spacing(spacing)
+            } else {
+                column![launcher_entry].max_width(width).spacing(spacing)
+            };
+
+            if self.config.show_type_filter && !self.alt_tab {
+                let chips = ResultKind::ALL.into_iter().map(|kind| {
+                    let style = if self.kind_filter.contains(&kind) {
+                        Button::Suggested
+                    } else {
+                        Button::Standard
+                    };
+                    button(text::body(kind.label()))
+                        .style(style)
+                        .padding([4, 12])
+                        .on_press(Message::ToggleKind(kind))
+                        .into()
+                });
+                content = content.push(row(chips.collect()).spacing(8));
+            }
+
+            if !self.alt_tab && !self.favorites.is_empty() {
+                let favorites = self.favorites.iter().enumerate().map(|(i, entry)| {
+                    button(entry.icon.as_cosmic_icon().size(32))
+                        .style(Button::Icon)
+                        .padding(8)
+                        .on_press(Message::LaunchFavorite(i))
+                        .into()
+                });
+                content = content.push(row(favorites.collect()).spacing(8));
+            }
+
+            if let Some(feedback) = &self.feedback {
+                content = content.push(text::caption(feedback));
+            }
+
+            if self.input_truncated {
+                content = content.push(text::caption(fl!(
+                    "input-truncated",
+                    max = self.config.max_input_length
+                )));
+            }
+
+            let completion = if self.config.autocomplete_on_right && !self.alt_tab {
+                self.completion()
             } else {
-                column![launcher_entry].max_width(600).spacing(16)
+                None
             };
+            if let Some(completion) = completion {
+                content = content.push(text::caption(format!("{completion} →")).style(
+                    theme::Text::Custom(|t| {
+                        let mut color: Color = t.cosmic().on_bg_color().into();
+                        color.a = 0.5;
+                        cosmic::iced::widget::text::Appearance { color: Some(color) }
+                    }),
+                ));
+            }
+
+            if self.sticky && !self.alt_tab {
+                content = content.push(text::caption(fl!("sticky-mode")));
+            }
+
+            if self.show_recents && !self.input_value.is_empty() {
+                content = content.push(text::caption(fl!("recent-results")));
+            }
 
             if !buttons.is_empty() {
-                content = content.push(components::list::column(buttons));
+                content = content.push(
+                    container(
+                        scrollable(components::list::column(buttons))
+                            .id(RESULTS_SCROLL_ID.clone())
+                            .on_scroll(|viewport| {
+                                Message::ResultsScrolled(viewport.absolute_offset().y)
+                            }),
+                    )
+                    .max_height(self.config.max_results_height),
+                );
+            }
+
+            let hidden = self.hidden_result_count();
+            if hidden > 0 && !self.alt_tab {
+                content = content.push(
+                    button(text::body(fl!("show-more-results", count = hidden)))
+                        .style(Button::Link)
+                        .on_press(Message::ShowAll),
+                );
             }
 
+            if let Some(latency) = self.search_latency.filter(|_| self.debug_timing) {
+                content = content.push(
+                    text::caption(format!("search: {} ms", latency.as_millis()))
+                        .width(Length::Fill)
+                        .horizontal_alignment(Horizontal::Right),
+                );
+            }
+
+            let preview = self
+                .visible_item(self.hover_preview.unwrap_or(self.focused))
+                .and_then(|item| self.file_previews.get(&item.id))
+                .map(file_preview);
+            let content: Element<_> = if let Some(preview) = preview {
+                row(vec![content.into(), preview]).spacing(16).into()
+            } else {
+                content.into()
+            };
+
             let window = container(content)
                 .style(Container::Custom(Box::new(|theme| container::Appearance {
                     text_color: Some(theme.cosmic().on_bg_color().into()),
@@ -808,7 +3304,12 @@ impl cosmic::Application for CosmicLauncher {
                     },
                     shadow: Shadow::default(),
                 })))
-                .padding([24, 32]);
+                .padding(self.config.window_padding);
+            let window = if self.fullscreen() {
+                window.width(Length::Fill).height(Length::Fill)
+            } else {
+                window
+            };
 
             return if self.menu.is_some() {
                 mouse_area(window)
@@ -820,6 +3321,16 @@ impl cosmic::Application for CosmicLauncher {
             };
         }
 
+        if id == *BACKDROP_ID {
+            return mouse_area(
+                container(horizontal_space(Length::Fill))
+                    .width(Length::Fill)
+                    .height(Length::Fill),
+            )
+            .on_press(Message::Hide)
+            .into();
+        }
+
         if id == *MENU_ID {
             let Some((i, options)) = self.menu.as_ref() else {
                 return container(horizontal_space(Length::Fixed(1.0)))
@@ -827,15 +3338,63 @@ impl cosmic::Application for CosmicLauncher {
                     .height(Length::Fixed(1.0))
                     .into();
             };
-            let list_column = Column::with_children(options.iter().map(|option| {
-                menu_button(text::body(&option.name))
-                    .on_press(Message::MenuButton(*i, option.id))
-                    .into()
-            }))
-            .padding([8, 0]);
+            let show_filter = self.menu_filter_shown();
+            let visible_options = self.visible_menu_options();
+
+            let mut menu_content = Column::new();
+            let confirming = self
+                .confirm_option
+                .and_then(|(_, option)| options.iter().find(|o| o.id == option));
+            if let Some(option) = confirming {
+                menu_content = menu_content
+                    .push(
+                        container(text::body(fl!(
+                            "confirm-option",
+                            option = option.name.as_str()
+                        )))
+                        .padding([8, 24]),
+                    )
+                    .push(
+                        menu_button(text::body(option.name.as_str()))
+                            .on_press(Message::MenuButton(*i, option.id)),
+                    )
+                    .push(
+                        menu_button(text::body(fl!("cancel")))
+                            .on_press(Message::CancelConfirmation),
+                    )
+                    .padding([0, 0, 8, 0]);
+            } else if show_filter {
+                let mut filter = text_input::search_input(fl!("filter-options"), &self.menu_filter)
+                    .on_input(Message::MenuFilterChanged)
+                    .always_active();
+                let submitted = self
+                    .menu_selected
+                    .and_then(|selected| visible_options.get(selected))
+                    .or_else(|| visible_options.first());
+                if let Some(option) = submitted {
+                    filter = filter.on_submit(Message::MenuButton(*i, option.id));
+                }
+                menu_content = menu_content.push(container(filter).padding([8, 8, 0, 8]));
+            }
+
+            if confirming.is_none() {
+                let list_column =
+                    Column::with_children(visible_options.iter().enumerate().map(|(j, option)| {
+                        let button = menu_button(text::body(&option.name))
+                            .on_press(Message::MenuButton(*i, option.id));
+                        if self.menu_selected == Some(j) {
+                            button.style(selected_menu_button()).into()
+                        } else {
+                            button.into()
+                        }
+                    }))
+                    .padding([8, 0]);
+                menu_content =
+                    menu_content.push(scrollable(list_column).id(MENU_SCROLL_ID.clone()));
+            }
 
             return container(
-                container(scrollable(list_column)).style(theme::Container::custom(|theme| {
+                container(menu_content).style(theme::Container::custom(|theme| {
                     let cosmic = theme.cosmic();
                     let corners = cosmic.corner_radii;
                     ContainerAppearance {
@@ -862,12 +3421,26 @@ impl cosmic::Application for CosmicLauncher {
     }
 
     fn subscription(&self) -> Subscription<Self::Message> {
-        Subscription::batch(vec![
+        let mut subscriptions = vec![
             launcher::subscription(0).map(Message::LauncherEvent),
+            self.core
+                .watch_config::<Config>(Self::APP_ID)
+                .map(|update| {
+                    for err in update.errors {
+                        tracing::error!("failed to load launcher config: {err}");
+                    }
+                    Message::Config(update.config)
+                }),
+            self.core
+                .watch_config::<ThemeMode>(THEME_MODE_ID)
+                .map(|_| Message::ThemeChanged),
             listen_raw(|e, status| match e {
                 cosmic::iced::Event::PlatformSpecific(PlatformSpecific::Wayland(
                     wayland::Event::Layer(e, ..),
                 )) => Some(Message::Layer(e)),
+                cosmic::iced::Event::PlatformSpecific(PlatformSpecific::Wayland(
+                    wayland::Event::Output(e, output),
+                )) => Some(Message::Output(e, output)),
                 cosmic::iced::Event::Keyboard(iced::keyboard::Event::KeyReleased {
                     key, ..
                 }) => match key {
@@ -886,33 +3459,290 @@ impl cosmic::Application for CosmicLauncher {
                     Key::Character(c) if modifiers.control() && (c == "n" || c == "j") => {
                         Some(Message::KeyboardNav(keyboard_nav::Message::FocusNext))
                     }
-                    Key::Character(c) if modifiers.control() => {
-                        let nums = (0..10)
-                            .map(|n| (n.to_string(), ((n + 10) % 10) - 1))
-                            .collect::<Vec<_>>();
-                        nums.iter()
-                            .find_map(|n| (n.0 == c).then(|| Message::Activate(Some(n.1))))
+                    // Ctrl + Alt + number is left to the keybindings.
+                    // The digits of the numpad arrive as the same characters while Num Lock is on.
+                    Key::Character(c) if modifiers.control() && !modifiers.alt() => {
+                        let mut chars = c.chars();
+                        let digit = chars
+                            .next()
+                            .and_then(|ch| ch.to_digit(10))
+                            .filter(|_| chars.next().is_none());
+                        digit
+                            .map(|digit| Message::Activate(Some(digit_activation(digit))))
+                            .or(Some(Message::KeyPressed(Key::Character(c), modifiers)))
                     }
-                    Key::Named(Named::ArrowUp) => {
+                    // With modifiers, the arrows may be bound to other actions.
+                    Key::Named(Named::ArrowUp) if modifiers.is_empty() => {
                         Some(Message::KeyboardNav(keyboard_nav::Message::FocusPrevious))
                     }
-                    Key::Named(Named::ArrowDown) => {
+                    Key::Named(Named::ArrowDown) if modifiers.is_empty() => {
                         Some(Message::KeyboardNav(keyboard_nav::Message::FocusNext))
                     }
-                    Key::Named(Named::Escape) => Some(Message::Hide),
+                    Key::Named(Named::Escape) => Some(Message::Escape),
                     Key::Named(Named::Tab) => Some(Message::TabPress),
                     Key::Named(Named::Backspace)
                         if matches!(status, Status::Ignored) && modifiers.is_empty() =>
                     {
                         Some(Message::Backspace)
                     }
-                    _ => None,
+                    // The input handles this itself while focused.
+                    Key::Named(Named::Backspace)
+                        if matches!(status, Status::Ignored) && modifiers == Modifiers::CTRL =>
+                    {
+                        Some(Message::DeleteWord)
+                    }
+                    _ if text.is_some()
+                        && matches!(status, Status::Captured)
+                        && (modifiers.is_empty() || modifiers == Modifiers::SHIFT) =>
+                    {
+                        Some(Message::KeyTyped(key, modifiers))
+                    }
+                    _ => Some(Message::KeyPressed(key, modifiers)),
                 },
                 cosmic::iced::Event::Mouse(iced::mouse::Event::CursorMoved { position }) => {
                     Some(Message::CursorMoved(position))
                 }
                 _ => None,
             }),
-        ])
+        ];
+
+        if !self.appeared.is_empty() {
+            subscriptions
+                .push(iced::time::every(Duration::from_millis(16)).map(|_| Message::AnimationTick));
+        }
+        if self.tx.is_some() && !self.pending_requests.is_empty() {
+            subscriptions
+                .push(iced::time::every(SEND_RETRY_INTERVAL).map(|_| Message::RetryRequests));
+        }
+
+        Subscription::batch(subscriptions)
+    }
+}
+
+/// Sends the queued requests in order without waiting for room in the channel, keeping the
+/// one which didn't fit at the front of the queue. Returns whether the channel was closed.
+fn flush_queue<T>(queue: &mut VecDeque<T>, tx: &mpsc::Sender<T>) -> bool {
+    while let Some(request) = queue.pop_front() {
+        match tx.try_send(request) {
+            Ok(()) => {}
+            Err(mpsc::error::TrySendError::Full(request)) => {
+                queue.push_front(request);
+                return false;
+            }
+            Err(mpsc::error::TrySendError::Closed(request)) => {
+                queue.push_front(request);
+                return true;
+            }
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A launcher as started by the service, with the default config.
+    fn headless() -> CosmicLauncher {
+        let (mut launcher, _) =
+            CosmicLauncher::init(Core::default(), Args::parse_from(["cosmic-launcher"]));
+        launcher.config = Config::default();
+        launcher
+    }
+
+    fn toggle(launcher: &mut CosmicLauncher) {
+        let _ = launcher.dbus_activation(cosmic::app::DbusActivationMessage {
+            activation_token: None,
+            desktop_startup_id: None,
+            msg: DbusActivationDetails::Activate,
+        });
+    }
+
+    #[test]
+    fn toggling_quickly_cancels_the_pending_surface() {
+        let mut launcher = headless();
+
+        toggle(&mut launcher);
+        assert!(launcher.active_surface && launcher.wait_for_result);
+        toggle(&mut launcher);
+        assert!(!launcher.active_surface && !launcher.wait_for_result);
+        assert!(!launcher.surface_created);
+        toggle(&mut launcher);
+        assert!(launcher.active_surface && launcher.wait_for_result);
+
+        let _ = launcher.update(Message::LauncherEvent(launcher::Event::Response(
+            pop_launcher::Response::Update(Vec::new()),
+        )));
+        assert!(launcher.active_surface && !launcher.wait_for_result);
+        assert!(launcher.surface_created);
+    }
+
+    /// Opens the context menu of the first result, as if it was right-clicked.
+    fn open_menu(launcher: &mut CosmicLauncher) {
+        launcher.active_surface = true;
+        launcher.menu = Some((
+            0,
+            vec![ContextOption {
+                id: 0,
+                name: "Remove".to_string(),
+            }],
+        ));
+    }
+
+    /// Whether the command destroys a popup, the only platform specific action closing the
+    /// menu issues.
+    fn destroys_popup(command: Command<Message>) -> bool {
+        command.actions().into_iter().any(|action| {
+            matches!(
+                action,
+                cosmic::iced_runtime::command::Action::PlatformSpecific(_)
+            )
+        })
+    }
+
+    #[test]
+    fn typing_closes_the_menu() {
+        let mut launcher = headless();
+        open_menu(&mut launcher);
+
+        let command = launcher.update(Message::InputChanged("a".to_string()));
+        assert!(launcher.menu.is_none());
+        assert!(destroys_popup(command));
+        assert_eq!(launcher.input_value, "a");
+    }
+
+    #[test]
+    fn escape_and_clicking_outside_close_only_the_menu() {
+        for message in [Message::Escape, Message::Hide, Message::CloseContextMenu] {
+            let mut launcher = headless();
+            open_menu(&mut launcher);
+
+            let command = launcher.update(message);
+            assert!(launcher.menu.is_none());
+            assert!(destroys_popup(command));
+            assert!(launcher.active_surface);
+        }
+    }
+
+    #[test]
+    fn requests_before_started_are_sent_once_it_is() {
+        let mut launcher = headless();
+        launcher.send(launcher::Request::Activate(3));
+        assert!(launcher.tx.is_none());
+        assert_eq!(launcher.pending_requests.len(), 1);
+
+        let (tx, mut rx) = mpsc::channel(8);
+        let _ = launcher.update(Message::LauncherEvent(launcher::Event::Started(tx)));
+        assert!(launcher.pending_requests.is_empty());
+        assert!(matches!(rx.try_recv(), Ok(launcher::Request::Search(query)) if query.is_empty()));
+        assert!(matches!(rx.try_recv(), Ok(launcher::Request::Activate(3))));
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn requests_wait_for_a_full_channel_and_searches_replace_each_other() {
+        let mut launcher = headless();
+        let (tx, mut rx) = mpsc::channel(1);
+        launcher.tx = Some(tx);
+
+        launcher.send(launcher::Request::Activate(0));
+        launcher.send(launcher::Request::Search("a".to_string()));
+        launcher.send(launcher::Request::Activate(1));
+        launcher.send(launcher::Request::Search("b".to_string()));
+        assert_eq!(launcher.pending_requests.len(), 2);
+        assert!(matches!(
+            launcher.pending_requests.back(),
+            Some(launcher::Request::Search(query)) if query == "b"
+        ));
+
+        assert!(matches!(rx.try_recv(), Ok(launcher::Request::Activate(0))));
+        launcher.flush_requests();
+        assert!(matches!(rx.try_recv(), Ok(launcher::Request::Activate(1))));
+        assert_eq!(launcher.pending_requests.len(), 1);
+
+        // The search is kept for the restarted service.
+        drop(rx);
+        launcher.flush_requests();
+        assert!(launcher.tx.is_none());
+        assert!(matches!(
+            launcher.pending_requests.front(),
+            Some(launcher::Request::Search(query)) if query == "b"
+        ));
+    }
+
+    #[test]
+    fn flushing_into_a_full_channel_keeps_the_rest_queued() {
+        let (tx, mut rx) = mpsc::channel(1);
+        let mut queue = VecDeque::from([1, 2, 3]);
+
+        assert!(!flush_queue(&mut queue, &tx));
+        assert_eq!(queue, [2, 3]);
+        assert_eq!(rx.try_recv(), Ok(1));
+
+        assert!(!flush_queue(&mut queue, &tx));
+        assert_eq!(queue, [3]);
+        assert_eq!(rx.try_recv(), Ok(2));
+
+        drop(rx);
+        assert!(flush_queue(&mut queue, &tx));
+        assert_eq!(queue, [3]);
+    }
+
+    #[test]
+    fn digits_activate_results_in_keyboard_order() {
+        for digit in 1..=9 {
+            assert_eq!(digit_activation(digit), digit as usize - 1);
+        }
+        assert_eq!(digit_activation(0), 9);
+        for i in 0..10 {
+            let digit = activation_digit(i).and_then(|digit| u32::try_from(digit).ok());
+            assert_eq!(digit.map(digit_activation), Some(i));
+        }
+    }
+
+    fn result(id: u32, name: &str, window: Option<(u32, u32)>) -> SearchResult {
+        SearchResult {
+            id,
+            name: name.to_string(),
+            description: String::new(),
+            icon: None,
+            category_icon: None,
+            window,
+        }
+    }
+
+    #[test]
+    fn deduplicate_keeps_the_first_of_each_name() {
+        let mut list = vec![
+            result(0, "Files", None),
+            result(1, "Terminal", None),
+            result(2, " files ", None),
+            result(3, "Terminal Emulator", None),
+            result(4, "Fîles", None),
+            result(5, "Terminal", Some((0, 1))),
+            result(6, "Terminal", Some((0, 2))),
+        ];
+        deduplicate(&mut list);
+
+        let ids: Vec<u32> = list.iter().map(|item| item.id).collect();
+        assert_eq!(ids, [0, 1, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn flatpak_id_of_exported_entries_only() {
+        assert_eq!(
+            flatpak_id(Path::new(
+                "/var/lib/flatpak/exports/share/applications/org.gnome.Maps.desktop"
+            ))
+            .as_deref(),
+            Some("org.gnome.Maps")
+        );
+        assert_eq!(
+            flatpak_id(Path::new("/usr/share/applications/org.gnome.Maps.desktop")),
+            None
+        );
+        assert_eq!(flatpak_id(Path::new("/var/lib/flatpak")), None);
+        assert_eq!(flatpak_id(Path::new("flatpak.desktop")), None);
+        assert_eq!(flatpak_id(Path::new("")), None);
     }
 }
@@ -1,8 +1,694 @@
+use cosmic::{
+    cosmic_config::{self, cosmic_config_derive::CosmicConfigEntry, CosmicConfigEntry},
+    iced::{
+        keyboard::{key::Named, Key, Modifiers},
+        wayland::{
+            actions::layer_surface::IcedMargin,
+            layer_surface::{Anchor, Layer},
+        },
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::{
+    app::ResultKind,
+    keybinds::{Action, KeyBinding, Modifier},
+};
+
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
+pub const MIN_UI_SCALE: f32 = 0.5;
+pub const MAX_UI_SCALE: f32 = 3.0;
+const DEFAULT_WINDOW_WIDTH: f32 = 600.0;
+const MIN_WINDOW_WIDTH: f32 = 320.0;
+
 pub fn profile() -> &'static str {
     std::env!("OUT_DIR")
         .split(std::path::MAIN_SEPARATOR)
         .nth_back(3)
         .unwrap_or("unknown")
 }
+
+#[derive(Debug, Clone, CosmicConfigEntry, PartialEq, Deserialize, Serialize)]
+#[version = 1]
+pub struct Config {
+    pub keybindings: HashMap<KeyBinding, Action>,
+    /// Filter the displayed results locally while waiting for the backend to respond.
+    pub local_filter: bool,
+    /// Center the window vertically while its estimated height is below this many pixels.
+    pub center_below_height: Option<f32>,
+    /// Where the window is placed on the output.
+    pub position: LauncherPosition,
+    /// Show a pane with details about the focused result next to the list.
+    pub show_preview: bool,
+    /// Milliseconds a result must be hovered before the preview switches to it.
+    pub hover_preview_delay: u64,
+    /// Number every result row by its position in the list.
+    pub show_index_numbers: bool,
+    /// Activate results with number keys without Ctrl while the input is empty.
+    pub bare_number_activation: bool,
+    /// Icon theme used for result icons instead of the system icon theme.
+    pub icon_theme: Option<String>,
+    /// Complete the query to the name of the top result when Right is pressed at its end.
+    pub autocomplete_on_right: bool,
+    /// Open context menus on right click or with the context menu keybindings.
+    pub context_menus_enabled: bool,
+    /// Size limits of the context menu popup.
+    pub context_menu: ContextMenuLimits,
+    /// Shell layer the window is placed on.
+    pub layer: LauncherLayer,
+    /// Longer input, e.g. from a paste, is cut to this many characters.
+    pub max_input_length: usize,
+    /// Results of queries with these prefixes are copied to the clipboard instead of launched.
+    pub copy_on_activate: Vec<CopyOnActivate>,
+    /// Fade in results that weren't shown before. Disable to reduce motion.
+    pub animate_results: bool,
+    /// Briefly highlight the activated result before hiding. Requires `animate_results`.
+    pub activation_flash: bool,
+    /// Queries starting with this prefix search emojis and symbols to copy. Empty to disable.
+    pub emoji_prefix: String,
+    /// Collapse the windows of the same application into one expandable result.
+    pub group_windows: bool,
+    /// Stay open after activating a result, clearing the query for the next one.
+    pub sticky: bool,
+    /// Space between the search input and the results, and between other rows of the window.
+    pub content_spacing: u16,
+    /// Vertical and horizontal padding inside the window border.
+    pub window_padding: [u16; 2],
+    /// Show the command line of app results below their description.
+    pub show_exec: bool,
+    /// The result list scrolls once it grows taller than this many pixels.
+    pub max_results_height: f32,
+    /// Desktop ids of the apps shown as icons below the search input, e.g. `"firefox"`.
+    pub favorites: Vec<String>,
+    /// Command which runs the exec line appended to it in a terminal, for desktop entries
+    /// with `Terminal=true`.
+    pub terminal: String,
+    /// Show a row of toggles which narrow the results to apps, files, windows or web searches.
+    pub show_type_filter: bool,
+    /// Show when file results were last opened, from the XDG recently used files.
+    pub show_recently_used: bool,
+    /// Scale of the launcher UI, on top of the scale of the output.
+    pub ui_scale: f32,
+    /// Hide results with the same name as a higher ranked result.
+    pub deduplicate_results: bool,
+    /// Draw the border of the search input in the accent color.
+    pub accent_input_border: bool,
+    /// Rank app results by how often and how recently they were launched.
+    pub frecency_ranking: bool,
+    pub frecency_weights: FrecencyWeights,
+    /// Show bold and italic markup in descriptions, dropping any other tags.
+    pub render_markup: bool,
+    /// Commands added to the context menu of results.
+    pub custom_actions: Vec<CustomAction>,
+    /// Corner radius of result rows, or the theme's small radius if unset.
+    pub result_corner_radius: Option<f32>,
+    /// Corner radius of hovered and pressed result rows, or the same as other rows if unset.
+    pub hovered_corner_radius: Option<f32>,
+    /// Fill the whole output, with larger rows for touch, when outputs are narrower than this
+    /// many logical pixels.
+    pub fullscreen_below_width: Option<u32>,
+    /// Context options whose names contain any of these, ignoring case, e.g. `"quit"`, are only
+    /// acted on after confirming them.
+    pub confirm_context_options: Vec<String>,
+    /// Show the `Comment` of an app's desktop entry for results without a description.
+    pub comment_descriptions: bool,
+    /// Show at most this many lines of a result's description, or all if unset.
+    pub max_desc_lines: Option<usize>,
+    /// Allow typing results into the previously focused app with `wtype`, which uses the
+    /// virtual keyboard protocol.
+    pub type_results: bool,
+    /// Move the result last activated for a query to the top when searching it again.
+    pub learn_selections: bool,
+    /// A result pinned to the top which runs a note-taking command.
+    pub scratchpad: Option<Scratchpad>,
+    /// Cover the output behind the launcher with a transparent surface which hides it when
+    /// clicked, instead of relying on the launcher losing focus.
+    pub click_outside_backdrop: bool,
+    /// Keep as many results as fit the height of the output, instead of ten.
+    pub fit_results_to_height: bool,
+    /// Keep at most this many results regardless of the output height, or all of them with 0.
+    /// The results past the tenth are reached by scrolling, without a Ctrl + digit shortcut.
+    pub max_results: Option<usize>,
+    /// Direction of the result rows, which mirror for right-to-left languages.
+    pub text_direction: TextDirection,
+    /// Show the value of arithmetic queries, copied when activated.
+    pub calculator: bool,
+    /// URL searching the web for `{query}`, offered below the results of non-empty queries,
+    /// e.g. `"https://duckduckgo.com/?q={query}"`.
+    pub web_search: Option<String>,
+    /// Show a badge with the source of each result, such as its kind or plugin.
+    pub show_source: bool,
+    /// Label results with letters, which activate them when typed after the leader keybinding.
+    pub letter_labels: bool,
+    /// Select a query filled in when the launcher opens or by a result, so that typing
+    /// replaces it.
+    pub select_prefilled_query: bool,
+    /// Event of the freedesktop sound theme played when a result is activated, e.g.
+    /// `"button-pressed"`.
+    pub activation_sound: Option<String>,
+    /// Offer the actions of the launcher as results for queries starting with `>`.
+    pub command_palette: bool,
+    /// Order window results by when they were last switched to from the launcher.
+    pub windows_recency: bool,
+    /// Convert queries such as `10 km in mi` between units, and between currencies once
+    /// exchange rates were fetched.
+    pub unit_conversion: bool,
+    /// URL of daily exchange rates in the format of the Frankfurter API, fetched with curl
+    /// when set, e.g. `"https://api.frankfurter.app/latest"`.
+    pub exchange_rates_url: Option<String>,
+    /// Take the keyboard focus when opened. Without it, the launcher only shows what it is
+    /// told over D-Bus, e.g. in kiosks driven by scripts.
+    pub grab_keyboard: bool,
+    /// Send queries to pop-launcher with their accented letters replaced by their base
+    /// letters, for apps whose names are written without them.
+    pub fold_search_query: bool,
+    /// In sticky mode, Escape only clears the query, and hides the launcher when pressed again
+    /// shortly after.
+    pub sticky_double_escape: bool,
+    /// Milliseconds without typing after which the query is searched. Clearing the query
+    /// searches right away.
+    pub search_debounce: u64,
+    /// Keep the surface of the launcher while hidden, so that it opens faster. Off by default, as
+    /// layer surfaces can't be unmapped: the hidden one is shrunk to 1×1 on the background layer,
+    /// and stays on the output where it was first opened instead of following the active output.
+    /// The surface isn't kept with `click_outside_backdrop`.
+    pub keep_surface: bool,
+    /// Bold the parts of result names which match the query.
+    pub highlight_matches: bool,
+    /// Width of the launcher in logical pixels, besides the preview pane. At least 320.
+    pub window_width: Option<f32>,
+    /// Remember the queries of activated results, recalled with Up and Down on an empty input.
+    pub search_history: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        // Not Ctrl + Shift + number: keys are matched by the character they type, which Shift
+        // turns into a symbol depending on the layout, and Ctrl + number activates a result.
+        // Assigning and launching also need chords of their own.
+        let quick_slots = (1..=9).flat_map(|slot| {
+            [
+                (
+                    KeyBinding::new([Modifier::Ctrl, Modifier::Alt], slot.to_string()),
+                    Action::AssignSlot(slot),
+                ),
+                (
+                    KeyBinding::new([Modifier::Alt], slot.to_string()),
+                    Action::LaunchSlot(slot),
+                ),
+            ]
+        });
+
+        Self {
+            keybindings: HashMap::from([
+                (KeyBinding::new(Vec::new(), "Enter"), Action::Activate),
+                (
+                    KeyBinding::new(Vec::new(), "ContextMenu"),
+                    Action::ContextMenu,
+                ),
+                (
+                    KeyBinding::new([Modifier::Shift], "F10"),
+                    Action::ContextMenu,
+                ),
+                (
+                    KeyBinding::new([Modifier::Ctrl], "h"),
+                    Action::ToggleRecents,
+                ),
+                (KeyBinding::new([Modifier::Ctrl], "r"), Action::RepeatLast),
+                (KeyBinding::new([Modifier::Ctrl], "s"), Action::ToggleSticky),
+                (
+                    KeyBinding::new([Modifier::Ctrl], "ArrowUp"),
+                    Action::ScrollUp,
+                ),
+                (
+                    KeyBinding::new([Modifier::Ctrl], "ArrowDown"),
+                    Action::ScrollDown,
+                ),
+                (
+                    KeyBinding::new([Modifier::Ctrl, Modifier::Shift], "a"),
+                    Action::ShowAll,
+                ),
+                (
+                    KeyBinding::new([Modifier::Ctrl, Modifier::Shift], "Delete"),
+                    Action::ClearHistory,
+                ),
+                (
+                    KeyBinding::new([Modifier::Ctrl, Modifier::Shift], "+"),
+                    Action::ZoomIn,
+                ),
+                (
+                    KeyBinding::new([Modifier::Ctrl, Modifier::Shift], "_"),
+                    Action::ZoomOut,
+                ),
+                (
+                    KeyBinding::new([Modifier::Alt], "Enter"),
+                    Action::TypeResult,
+                ),
+                (KeyBinding::new([Modifier::Ctrl], "g"), Action::Leader),
+                (
+                    KeyBinding::new([Modifier::Ctrl, Modifier::Shift], "h"),
+                    Action::ToggleHidden,
+                ),
+            ])
+            .into_iter()
+            .chain(quick_slots)
+            .collect(),
+            local_filter: false,
+            center_below_height: None,
+            position: LauncherPosition::default(),
+            show_preview: false,
+            hover_preview_delay: 400,
+            show_index_numbers: false,
+            bare_number_activation: false,
+            icon_theme: None,
+            autocomplete_on_right: false,
+            context_menus_enabled: true,
+            context_menu: ContextMenuLimits::default(),
+            layer: LauncherLayer::default(),
+            max_input_length: 512,
+            copy_on_activate: Vec::new(),
+            animate_results: false,
+            activation_flash: false,
+            emoji_prefix: String::from(":"),
+            group_windows: false,
+            sticky: false,
+            content_spacing: 16,
+            window_padding: [24, 32],
+            show_exec: false,
+            max_results_height: 640.0,
+            favorites: Vec::new(),
+            terminal: String::from("cosmic-term -e"),
+            show_type_filter: false,
+            show_recently_used: false,
+            ui_scale: 1.0,
+            deduplicate_results: false,
+            accent_input_border: false,
+            frecency_ranking: false,
+            frecency_weights: FrecencyWeights::default(),
+            render_markup: false,
+            custom_actions: Vec::new(),
+            result_corner_radius: None,
+            hovered_corner_radius: None,
+            fullscreen_below_width: None,
+            confirm_context_options: Vec::new(),
+            comment_descriptions: false,
+            max_desc_lines: None,
+            type_results: false,
+            learn_selections: false,
+            scratchpad: None,
+            click_outside_backdrop: false,
+            fit_results_to_height: false,
+            max_results: None,
+            text_direction: TextDirection::default(),
+            calculator: false,
+            web_search: None,
+            show_source: false,
+            letter_labels: false,
+            select_prefilled_query: false,
+            activation_sound: None,
+            command_palette: false,
+            windows_recency: false,
+            unit_conversion: false,
+            exchange_rates_url: None,
+            grab_keyboard: true,
+            fold_search_query: false,
+            sticky_double_escape: false,
+            search_debounce: 80,
+            keep_surface: false,
+            highlight_matches: true,
+            window_width: None,
+            search_history: true,
+        }
+    }
+}
+
+impl Config {
+    /// Loads the config, falling back to the defaults for any missing or malformed keys.
+    pub fn load(app_id: &str) -> Self {
+        match cosmic_config::Config::new(app_id, Self::VERSION) {
+            Ok(helper) => Self::get_entry(&helper).unwrap_or_else(|(errs, config)| {
+                for err in errs {
+                    tracing::error!("failed to load launcher config: {err}");
+                }
+                config
+            }),
+            Err(err) => {
+                tracing::error!("failed to open launcher config: {err}");
+                Self::default()
+            }
+        }
+    }
+
+    /// The UI scale, clamped to a usable range.
+    pub fn ui_scale(&self) -> f32 {
+        self.ui_scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE)
+    }
+
+    /// The width of the launcher, with a usable minimum.
+    pub fn window_width(&self) -> f32 {
+        self.window_width
+            .map_or(DEFAULT_WINDOW_WIDTH, |width| width.max(MIN_WINDOW_WIDTH))
+    }
+
+    /// Returns the action bound to the pressed key chord, if any.
+    ///
+    /// Bindings differing only in the case of their key or the order of their modifiers match
+    /// the same chord. The one whose key is spelled as typed wins, then the first as text.
+    pub fn action(&self, key: &Key, modifiers: Modifiers) -> Option<Action> {
+        self.keybindings
+            .iter()
+            .filter(|(binding, _)| binding.matches(key, modifiers))
+            .min_by_key(|(binding, _)| {
+                let exact = matches!(key, Key::Character(c) if c.as_str() == binding.key);
+                (!exact, binding.to_string())
+            })
+            .map(|(_, action)| action.clone())
+            .or_else(|| {
+                // Keybindings configured before the launch key was configurable don't bind it.
+                let unbound = !self.keybindings.values().any(|a| *a == Action::Activate);
+                (unbound && modifiers.is_empty() && *key == Key::Named(Named::Enter))
+                    .then_some(Action::Activate)
+            })
+    }
+}
+
+/// The context menu is sized to fit its longest option within these limits.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct ContextMenuLimits {
+    pub min_width: f32,
+    pub max_width: f32,
+    pub max_height: f32,
+    /// Show a field for filtering the options of menus with more than this many options.
+    pub filter_above: Option<usize>,
+}
+
+impl Default for ContextMenuLimits {
+    fn default() -> Self {
+        Self {
+            min_width: 160.0,
+            max_width: 300.0,
+            max_height: 800.0,
+            filter_above: Some(10),
+        }
+    }
+}
+
+/// How much launch counts and the time since the last launch contribute to the frecency ranking.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct FrecencyWeights {
+    pub frequency: f32,
+    pub recency: f32,
+}
+
+impl Default for FrecencyWeights {
+    fn default() -> Self {
+        Self {
+            frequency: 1.0,
+            recency: 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Edge {
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Margin {
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+    pub left: i32,
+}
+
+impl From<Margin> for IcedMargin {
+    fn from(margin: Margin) -> Self {
+        IcedMargin {
+            top: margin.top,
+            right: margin.right,
+            bottom: margin.bottom,
+            left: margin.left,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum LauncherPosition {
+    /// Anchored to the top of the output, slightly offset from its edge.
+    #[default]
+    Top,
+    /// Around the middle of the output. The top of the window stays a quarter of the way down
+    /// the output, so that it grows downward as results appear instead of moving.
+    Center,
+    /// Anchored to the given edges, offset from them by `margin`.
+    Custom { anchor: Vec<Edge>, margin: Margin },
+}
+
+impl LauncherPosition {
+    pub fn anchor(&self) -> Anchor {
+        match self {
+            Self::Top | Self::Center => Anchor::TOP,
+            Self::Custom { anchor, .. } => {
+                anchor.iter().fold(Anchor::empty(), |acc, edge| match edge {
+                    Edge::Top => acc | Anchor::TOP,
+                    Edge::Bottom => acc | Anchor::BOTTOM,
+                    Edge::Left => acc | Anchor::LEFT,
+                    Edge::Right => acc | Anchor::RIGHT,
+                })
+            }
+        }
+    }
+
+    /// The margin on an output `output_height` pixels high, if known.
+    pub fn margin(&self, output_height: Option<i32>) -> Margin {
+        match self {
+            Self::Top => Margin {
+                top: 16,
+                ..Margin::default()
+            },
+            Self::Center => Margin {
+                top: output_height.map_or(240, |height| height / 4),
+                ..Margin::default()
+            },
+            Self::Custom { margin, .. } => *margin,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum TextDirection {
+    /// Right to left for languages written that way, as requested by the desktop.
+    #[default]
+    Auto,
+    LeftToRight,
+    RightToLeft,
+}
+
+impl TextDirection {
+    pub fn is_rtl(self) -> bool {
+        match self {
+            Self::Auto => *crate::localize::RIGHT_TO_LEFT,
+            Self::LeftToRight => false,
+            Self::RightToLeft => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum LauncherLayer {
+    /// Above regular windows, but below fullscreen windows.
+    #[default]
+    Top,
+    /// Above everything, including fullscreen windows such as games.
+    Overlay,
+}
+
+impl From<LauncherLayer> for Layer {
+    fn from(layer: LauncherLayer) -> Self {
+        match layer {
+            LauncherLayer::Top => Layer::Top,
+            LauncherLayer::Overlay => Layer::Overlay,
+        }
+    }
+}
+
+/// Copies a field of a result to the clipboard when activated, e.g. for password managers.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CopyOnActivate {
+    /// Query prefix of the plugin whose results are copied, e.g. `"pass "`.
+    pub prefix: String,
+    pub field: CopyField,
+    /// Clear the clipboard after this many seconds.
+    pub clear_after: Option<u64>,
+    /// Also paste the copied field into the previously focused app with Ctrl+V, e.g. for
+    /// clipboard history, through `wtype` like `type_results`.
+    #[serde(default)]
+    pub paste: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum CopyField {
+    Name,
+    Description,
+}
+
+/// A context menu option which runs a command for the result.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CustomAction {
+    /// Name of the option in the context menu.
+    pub name: String,
+    /// Command to run, in which `{query}` and `{result}` are replaced with the query and the
+    /// name of the result, e.g. `"notify-send {result}"`.
+    pub command: String,
+    /// Kinds of results the option is added to, or all results if empty.
+    #[serde(default)]
+    pub kinds: Vec<ResultKind>,
+}
+
+impl CustomAction {
+    pub fn applies_to(&self, kind: Option<ResultKind>) -> bool {
+        self.kinds.is_empty() || kind.map_or(false, |kind| self.kinds.contains(&kind))
+    }
+
+    /// The command with its placeholders replaced by shell-quoted values, in a single pass so
+    /// that placeholders in the values themselves are left alone.
+    pub fn command_for(&self, query: &str, result: &str) -> String {
+        let quote = |value: &str| {
+            shlex::try_quote(value).map_or_else(|_| String::new(), |quoted| quoted.into_owned())
+        };
+        let mut command = String::with_capacity(self.command.len());
+        let mut rest = self.command.as_str();
+        while let Some(start) = rest.find('{') {
+            command.push_str(&rest[..start]);
+            rest = &rest[start..];
+            if let Some(after) = rest.strip_prefix("{query}") {
+                command.push_str(&quote(query));
+                rest = after;
+            } else if let Some(after) = rest.strip_prefix("{result}") {
+                command.push_str(&quote(result));
+                rest = after;
+            } else {
+                command.push('{');
+                rest = &rest[1..];
+            }
+        }
+        command.push_str(rest);
+        command
+    }
+}
+
+/// A result for taking quick notes, shown for an empty query and for its trigger word.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Scratchpad {
+    pub name: String,
+    /// Command run when the result is activated, e.g. `"cosmic-edit ~/notes.md"`.
+    pub command: String,
+    /// Query which shows the result, matched ignoring case, e.g. `"note"`.
+    pub trigger: String,
+}
+
+impl Scratchpad {
+    pub fn matches(&self, query: &str) -> bool {
+        query.is_empty() || query.eq_ignore_ascii_case(&self.trigger)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn activate_key_can_be_rebound() {
+        let config = Config {
+            keybindings: HashMap::from([(KeyBinding::new(Vec::new(), "Space"), Action::Activate)]),
+            ..Config::default()
+        };
+        let space = Key::Named(Named::Space);
+        let enter = Key::Named(Named::Enter);
+        assert_eq!(
+            config.action(&space, Modifiers::empty()),
+            Some(Action::Activate)
+        );
+        assert_eq!(config.action(&enter, Modifiers::empty()), None);
+    }
+
+    #[test]
+    fn activate_falls_back_to_enter_when_unbound() {
+        let config = Config {
+            keybindings: HashMap::from([(
+                KeyBinding::new([Modifier::Ctrl], "s"),
+                Action::ToggleSticky,
+            )]),
+            ..Config::default()
+        };
+        let enter = Key::Named(Named::Enter);
+        assert_eq!(
+            config.action(&enter, Modifiers::empty()),
+            Some(Action::Activate)
+        );
+        assert_eq!(config.action(&enter, Modifiers::CTRL), None);
+        assert_eq!(
+            Config::default().action(&enter, Modifiers::empty()),
+            Some(Action::Activate)
+        );
+    }
+
+    #[test]
+    fn ambiguous_bindings_resolve_the_same_way_every_time() {
+        let config = Config {
+            keybindings: HashMap::from([
+                (KeyBinding::new([Modifier::Ctrl], "K"), Action::ShowAll),
+                (KeyBinding::new([Modifier::Ctrl], "k"), Action::ToggleSticky),
+                (
+                    KeyBinding::new([Modifier::Ctrl, Modifier::Alt], "x"),
+                    Action::ShowAll,
+                ),
+                (
+                    KeyBinding::new([Modifier::Alt, Modifier::Ctrl], "x"),
+                    Action::ToggleSticky,
+                ),
+            ]),
+            ..Config::default()
+        };
+        assert_eq!(
+            config.action(&Key::Character("k".into()), Modifiers::CTRL),
+            Some(Action::ToggleSticky)
+        );
+        assert_eq!(
+            config.action(&Key::Character("K".into()), Modifiers::CTRL),
+            Some(Action::ShowAll)
+        );
+        assert_eq!(
+            config.action(
+                &Key::Character("x".into()),
+                Modifiers::CTRL | Modifiers::ALT
+            ),
+            Some(Action::ToggleSticky)
+        );
+    }
+
+    #[test]
+    fn custom_action_substitutes_placeholders_once() {
+        let action = CustomAction {
+            name: String::from("Echo"),
+            command: String::from("echo {query} {result} {other}"),
+            kinds: Vec::new(),
+        };
+        let command = action.command_for("{result} it's", "Firefox");
+        assert_eq!(
+            shlex::split(&command),
+            Some(vec![
+                String::from("echo"),
+                String::from("{result} it's"),
+                String::from("Firefox"),
+                String::from("{other}"),
+            ])
+        );
+    }
+}
@@ -0,0 +1,127 @@
+/// An emoji searchable by its name and keywords in the emoji picker mode.
+#[derive(Debug)]
+pub struct Emoji {
+    pub glyph: &'static str,
+    pub name: &'static str,
+    pub keywords: &'static [&'static str],
+}
+
+macro_rules! emoji {
+    ($glyph:literal, $name:literal $(, $keyword:literal)* $(,)?) => {
+        Emoji {
+            glyph: $glyph,
+            name: $name,
+            keywords: &[$($keyword),*],
+        }
+    };
+}
+
+pub static EMOJIS: &[Emoji] = &[
+    emoji!("😀", "grinning face", "smile", "happy"),
+    emoji!("😂", "face with tears of joy", "laugh", "lol", "funny"),
+    emoji!("🙂", "slightly smiling face", "smile"),
+    emoji!("😉", "winking face", "wink"),
+    emoji!("😊", "smiling face with smiling eyes", "blush", "happy"),
+    emoji!("😍", "smiling face with heart-eyes", "love", "crush"),
+    emoji!("😘", "face blowing a kiss", "kiss", "love"),
+    emoji!("😎", "smiling face with sunglasses", "cool"),
+    emoji!("🤔", "thinking face", "think", "hmm"),
+    emoji!("😐", "neutral face", "meh"),
+    emoji!("🙄", "face with rolling eyes", "eyeroll"),
+    emoji!("😴", "sleeping face", "sleep", "tired"),
+    emoji!("😢", "crying face", "sad", "tear"),
+    emoji!("😭", "loudly crying face", "sob", "sad"),
+    emoji!("😡", "enraged face", "angry", "mad"),
+    emoji!("😱", "face screaming in fear", "scream", "shock"),
+    emoji!("🤯", "exploding head", "mind blown", "shock"),
+    emoji!("🥳", "partying face", "party", "celebrate"),
+    emoji!("🤗", "smiling face with open hands", "hug"),
+    emoji!("🤷", "person shrugging", "shrug", "whatever"),
+    emoji!("🤦", "person facepalming", "facepalm"),
+    emoji!("🙏", "folded hands", "please", "thanks", "pray"),
+    emoji!("👍", "thumbs up", "yes", "like", "ok", "+1"),
+    emoji!("👎", "thumbs down", "no", "dislike", "-1"),
+    emoji!("👏", "clapping hands", "clap", "applause"),
+    emoji!("👋", "waving hand", "wave", "hello", "bye"),
+    emoji!("👌", "ok hand", "ok", "perfect"),
+    emoji!("✌️", "victory hand", "peace"),
+    emoji!("🤞", "crossed fingers", "luck", "hope"),
+    emoji!("💪", "flexed biceps", "strong", "muscle"),
+    emoji!("👀", "eyes", "look", "see"),
+    emoji!("🧠", "brain", "smart"),
+    emoji!("❤️", "red heart", "love", "heart"),
+    emoji!("💔", "broken heart", "heartbreak"),
+    emoji!("💯", "hundred points", "100", "perfect"),
+    emoji!("✨", "sparkles", "shiny", "magic"),
+    emoji!("🔥", "fire", "hot", "lit"),
+    emoji!("⭐", "star", "favorite"),
+    emoji!("⚡", "high voltage", "lightning", "zap"),
+    emoji!("☀️", "sun", "sunny", "weather"),
+    emoji!("🌙", "crescent moon", "night"),
+    emoji!("🌧️", "cloud with rain", "rain", "weather"),
+    emoji!("❄️", "snowflake", "snow", "cold"),
+    emoji!("🌈", "rainbow"),
+    emoji!("🎉", "party popper", "party", "tada", "celebrate"),
+    emoji!("🎁", "wrapped gift", "present", "birthday"),
+    emoji!("🎂", "birthday cake", "birthday", "cake"),
+    emoji!("☕", "hot beverage", "coffee", "tea"),
+    emoji!("🍺", "beer mug", "beer", "drink"),
+    emoji!("🍕", "pizza", "food"),
+    emoji!("🍎", "red apple", "apple", "fruit"),
+    emoji!("🐱", "cat face", "cat", "kitten"),
+    emoji!("🐶", "dog face", "dog", "puppy"),
+    emoji!("🐧", "penguin", "linux", "tux"),
+    emoji!("🦀", "crab", "rust", "ferris"),
+    emoji!("🐛", "bug", "insect"),
+    emoji!("🚀", "rocket", "launch", "ship"),
+    emoji!("💻", "laptop", "computer"),
+    emoji!("⌨️", "keyboard", "type"),
+    emoji!("📁", "file folder", "folder", "directory"),
+    emoji!("📝", "memo", "note", "write"),
+    emoji!("📌", "pushpin", "pin"),
+    emoji!("🔒", "locked", "lock", "secure"),
+    emoji!("🔑", "key", "password"),
+    emoji!("🔧", "wrench", "tool", "fix"),
+    emoji!("⚙️", "gear", "settings", "cog"),
+    emoji!("💡", "light bulb", "idea"),
+    emoji!("📅", "calendar", "date"),
+    emoji!("⏰", "alarm clock", "time", "alarm"),
+    emoji!("✅", "check mark button", "done", "yes"),
+    emoji!("❌", "cross mark", "no", "wrong"),
+    emoji!("⚠️", "warning", "caution"),
+    emoji!("❓", "red question mark", "question"),
+    emoji!("❗", "red exclamation mark", "exclamation", "important"),
+    emoji!("➡️", "right arrow", "arrow", "next"),
+    emoji!("⬅️", "left arrow", "arrow", "back"),
+    emoji!("→", "rightwards arrow", "arrow", "symbol"),
+    emoji!("←", "leftwards arrow", "arrow", "symbol"),
+    emoji!("•", "bullet", "dot", "symbol"),
+    emoji!("…", "horizontal ellipsis", "dots", "symbol"),
+    emoji!("—", "em dash", "dash", "symbol"),
+    emoji!("©", "copyright sign", "symbol"),
+    emoji!("®", "registered sign", "symbol"),
+    emoji!("™", "trade mark sign", "trademark", "symbol"),
+    emoji!("°", "degree sign", "temperature", "symbol"),
+    emoji!("±", "plus-minus sign", "math", "symbol"),
+    emoji!("×", "multiplication sign", "times", "math", "symbol"),
+    emoji!("÷", "division sign", "divide", "math", "symbol"),
+    emoji!("≠", "not equal to", "math", "symbol"),
+    emoji!("≈", "almost equal to", "approximately", "math", "symbol"),
+    emoji!("€", "euro sign", "currency", "money"),
+    emoji!("£", "pound sign", "currency", "money"),
+    emoji!("¥", "yen sign", "currency", "money"),
+];
+
+/// Emojis whose name or keywords contain every word of `query`.
+pub fn search(query: &str) -> impl Iterator<Item = &'static Emoji> {
+    let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+    EMOJIS.iter().filter(move |emoji| {
+        words.iter().all(|word| {
+            emoji.name.contains(word.as_str())
+                || emoji
+                    .keywords
+                    .iter()
+                    .any(|keyword| keyword.contains(word.as_str()))
+        })
+    })
+}
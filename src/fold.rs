@@ -0,0 +1,87 @@
+//! Folding of accented letters to their base letters, so that typing `cafe` matches `Café`
+//! and `Munchen` matches `München`.
+//!
+//! Letters are decomposed canonically and their combining marks dropped, whether they were
+//! typed precomposed or not. Letters without a decomposition, such as ß or ø, are folded with
+//! a table instead.
+
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+/// Letters which have no canonical decomposition, and the letters they fold to.
+const FOLDS: &[(char, &str)] = &[
+    ('ß', "ss"),
+    ('æ', "ae"),
+    ('Æ', "AE"),
+    ('œ', "oe"),
+    ('Œ', "OE"),
+    ('þ', "th"),
+    ('Þ', "TH"),
+    ('ø', "o"),
+    ('Ø', "O"),
+    ('đ', "d"),
+    ('Đ', "D"),
+    ('ð', "d"),
+    ('Ð', "D"),
+    ('ħ', "h"),
+    ('Ħ', "H"),
+    ('ı', "i"),
+    ('ł', "l"),
+    ('Ł', "L"),
+    ('ŧ', "t"),
+    ('Ŧ', "T"),
+];
+
+/// `text` with its accented letters replaced by their base letters, keeping their case.
+pub fn fold(text: &str) -> String {
+    let mut folded = String::with_capacity(text.len());
+    for c in text.nfd() {
+        if c.is_ascii() {
+            folded.push(c);
+        } else if is_combining_mark(c) {
+            // Accents, once separated from their letters.
+        } else if let Some((_, base)) = FOLDS.iter().find(|(letter, _)| *letter == c) {
+            folded.push_str(base);
+        } else {
+            folded.push(c);
+        }
+    }
+    folded
+}
+
+/// `text` folded and lowercased, for matching regardless of case and accents.
+pub fn normalize(text: &str) -> String {
+    fold(text).to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn precomposed_and_decomposed_letters_fold_alike() {
+        assert_eq!(fold("Café"), "Cafe");
+        assert_eq!(fold("Cafe\u{301}"), "Cafe");
+        assert_eq!(fold("München"), "Munchen");
+        assert_eq!(fold("Mu\u{308}nchen"), "Munchen");
+    }
+
+    #[test]
+    fn letters_beyond_latin_1_fold() {
+        assert_eq!(fold("ǎ ő Ǒ"), "a o O");
+        assert_eq!(fold("Tiếng Việt"), "Tieng Viet");
+        assert_eq!(fold("Tie\u{302}\u{301}ng Vie\u{323}\u{302}t"), "Tieng Viet");
+        assert_eq!(normalize("ĐẶNG"), "dang");
+    }
+
+    #[test]
+    fn letters_without_a_decomposition_fold_with_the_table() {
+        assert_eq!(fold("Straße"), "Strasse");
+        assert_eq!(fold("Øresund"), "Oresund");
+        assert_eq!(fold("Łódź"), "Lodz");
+    }
+
+    #[test]
+    fn other_scripts_are_kept() {
+        assert_eq!(fold("Привет 日本"), "Привет 日本");
+    }
+}
@@ -0,0 +1,147 @@
+//! Locating the query in result names, which pop-launcher matches without telling where.
+
+use std::ops::Range;
+
+use crate::fold;
+
+/// Splits `text` into byte ranges which are highlighted or not, depending on whether they
+/// match `query`. A contiguous match is preferred over a subsequence of scattered characters,
+/// and nothing is highlighted unless the whole query matches. Matching ignores case and
+/// accents, and the ranges always fall on char boundaries.
+pub fn segments(text: &str, query: &str) -> Vec<(Range<usize>, bool)> {
+    let query: Vec<char> = fold::normalize(query)
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    // The normalized form of each char of the text, which is longer for ligatures such as ß.
+    let chars: Vec<(usize, Vec<char>)> = text
+        .char_indices()
+        .map(|(i, c)| {
+            (
+                i,
+                fold::normalize(c.encode_utf8(&mut [0; 4]))
+                    .chars()
+                    .collect(),
+            )
+        })
+        .collect();
+
+    let matched = if query.is_empty() {
+        None
+    } else {
+        substring(&chars, &query).or_else(|| subsequence(&chars, &query))
+    };
+    let Some(matched) = matched else {
+        return vec![(0..text.len(), false)];
+    };
+
+    let mut segments: Vec<(Range<usize>, bool)> = Vec::new();
+    for (n, (start, _)) in chars.iter().enumerate() {
+        let end = chars.get(n + 1).map_or(text.len(), |(next, _)| *next);
+        match segments.last_mut() {
+            Some((range, highlighted)) if *highlighted == matched[n] => range.end = end,
+            _ => segments.push((*start..end, matched[n])),
+        }
+    }
+    segments
+}
+
+/// Which chars of the text are part of the first contiguous occurrence of the query.
+fn substring(chars: &[(usize, Vec<char>)], query: &[char]) -> Option<Vec<bool>> {
+    (0..chars.len()).find_map(|start| {
+        let mut remaining = query;
+        let mut matched = vec![false; chars.len()];
+        for (n, (_, normalized)) in chars.iter().enumerate().skip(start) {
+            if remaining.is_empty() {
+                break;
+            }
+            let len = normalized.len().min(remaining.len());
+            if normalized[..len] != remaining[..len] {
+                return None;
+            }
+            remaining = &remaining[len..];
+            matched[n] = true;
+        }
+        remaining.is_empty().then_some(matched)
+    })
+}
+
+/// Which chars of the text match the chars of the query in order, taking the earliest ones.
+fn subsequence(chars: &[(usize, Vec<char>)], query: &[char]) -> Option<Vec<bool>> {
+    let mut remaining = query.iter().peekable();
+    let mut matched = vec![false; chars.len()];
+    for (n, (_, normalized)) in chars.iter().enumerate() {
+        for c in normalized {
+            if remaining.peek() == Some(&c) {
+                remaining.next();
+                matched[n] = true;
+            }
+        }
+    }
+    remaining.peek().is_none().then_some(matched)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The segments as slices of `text`, checking that they cover it on char boundaries.
+    fn highlighted<'a>(text: &'a str, query: &str) -> Vec<(&'a str, bool)> {
+        let segments = segments(text, query);
+        let mut end = 0;
+        for (range, _) in &segments {
+            assert_eq!(range.start, end);
+            assert!(text.is_char_boundary(range.start) && text.is_char_boundary(range.end));
+            end = range.end;
+        }
+        assert_eq!(end, text.len());
+        segments
+            .into_iter()
+            .map(|(range, highlighted)| (&text[range], highlighted))
+            .collect()
+    }
+
+    #[test]
+    fn substring_is_preferred() {
+        assert_eq!(
+            highlighted("Firefox", "FOX"),
+            [("Fire", false), ("fox", true)]
+        );
+    }
+
+    #[test]
+    fn subsequence_matches_scattered_chars() {
+        assert_eq!(
+            highlighted("Firefox", "ffx"),
+            [
+                ("F", true),
+                ("ire", false),
+                ("f", true),
+                ("o", false),
+                ("x", true)
+            ]
+        );
+    }
+
+    #[test]
+    fn nothing_is_highlighted_without_a_match() {
+        assert_eq!(highlighted("Firefox", "fz"), [("Firefox", false)]);
+        assert_eq!(highlighted("Firefox", ""), [("Firefox", false)]);
+    }
+
+    #[test]
+    fn multi_byte_chars_match_their_base_letters() {
+        assert_eq!(highlighted("Café", "cafe"), [("Café", true)]);
+        assert_eq!(highlighted("Café", "fé"), [("Ca", false), ("fé", true)]);
+        assert_eq!(
+            highlighted("München", "unch"),
+            [("M", false), ("ünch", true), ("en", false)]
+        );
+        assert_eq!(
+            highlighted("Straße", "sse"),
+            [("Stra", false), ("ße", true)]
+        );
+        highlighted("Cafe\u{301}", "cafe");
+        highlighted("日本語", "本");
+    }
+}
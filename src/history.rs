@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+use crate::store;
+
+const HISTORY_FILE: &str = "history.json";
+/// Number of queries kept, beyond which the oldest are forgotten.
+const MAX_QUERIES: usize = 50;
+
+/// Queries searched from the launcher, recalled with Up and Down on an empty input.
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct SearchHistory {
+    /// From the most recent, without duplicates.
+    pub queries: Vec<String>,
+}
+
+impl SearchHistory {
+    pub fn load() -> Self {
+        store::load_json(HISTORY_FILE)
+    }
+
+    pub fn save(&self) {
+        if let Err(err) = store::save_json(HISTORY_FILE, self) {
+            tracing::error!("failed to save search history: {err}");
+        }
+    }
+
+    /// Moves `query` to the front of the history.
+    pub fn push(&mut self, query: &str) {
+        self.queries.retain(|q| q != query);
+        self.queries.insert(0, query.to_string());
+        self.queries.truncate(MAX_QUERIES);
+    }
+}
+
+/// Deletes the stored search history.
+pub fn clear() -> std::io::Result<()> {
+    let Some(path) = store::data_path(HISTORY_FILE) else {
+        return Ok(());
+    };
+    match std::fs::remove_file(path) {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
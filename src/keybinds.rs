@@ -0,0 +1,103 @@
+use std::fmt;
+
+use cosmic::iced::keyboard::{Key, Modifiers};
+use serde::{Deserialize, Serialize};
+
+/// Launcher actions which can be bound to a key chord in the config.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum Action {
+    /// Activate the focused result. Bound to Enter by default. Keys which type text, such as
+    /// Space, are typed into the search input instead while it has the focus.
+    Activate,
+    /// Toggle the context menu of the focused result.
+    ContextMenu,
+    /// Launch the desktop entry with the given id, regardless of the query.
+    Launch(String),
+    /// Switch between the results for the current query and the recent results.
+    ToggleRecents,
+    /// Launch the most recently launched app again, when the input is empty.
+    RepeatLast,
+    /// Toggle sticky mode, which keeps the launcher open after activating a result.
+    ToggleSticky,
+    /// Scroll the result list up by a row, without moving the focus.
+    ScrollUp,
+    /// Scroll the result list down by a row, without moving the focus.
+    ScrollDown,
+    /// Show all results of the current query instead of the first ten.
+    ShowAll,
+    /// Assign the app of the focused result to the quick slot with the given number.
+    AssignSlot(u8),
+    /// Launch the app assigned to the quick slot with the given number.
+    LaunchSlot(u8),
+    /// Delete the launch and search history, after pressing the keybinding a second time to
+    /// confirm. Quick slots and learned selections are kept.
+    ClearHistory,
+    /// Increase the UI scale, saving it to the config.
+    ZoomIn,
+    /// Decrease the UI scale, saving it to the config.
+    ZoomOut,
+    /// Hide and type the focused result into the previously focused app, if `type_results` is
+    /// enabled.
+    TypeResult,
+    /// Activate the result labeled with the next letter typed, if `letter_labels` is enabled.
+    Leader,
+    /// Show or hide the apps whose desktop entries are hidden with `NoDisplay`.
+    ToggleHidden,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum Modifier {
+    Ctrl,
+    Alt,
+    Shift,
+    Super,
+}
+
+/// A key chord, e.g. `Shift + F10`.
+///
+/// `key` is either a character (matched case-insensitively) or the name of a
+/// named key as spelled by iced, e.g. `ContextMenu`, `F10` or `Space`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct KeyBinding {
+    pub modifiers: Vec<Modifier>,
+    pub key: String,
+}
+
+impl KeyBinding {
+    pub fn new(modifiers: impl Into<Vec<Modifier>>, key: impl Into<String>) -> Self {
+        Self {
+            modifiers: modifiers.into(),
+            key: key.into(),
+        }
+    }
+
+    pub fn matches(&self, key: &Key, modifiers: Modifiers) -> bool {
+        let key_matches = match key {
+            Key::Character(c) => c.as_str().eq_ignore_ascii_case(&self.key),
+            Key::Named(named) => format!("{named:?}") == self.key,
+            Key::Unidentified => false,
+        };
+
+        key_matches && self.modifiers() == modifiers
+    }
+
+    fn modifiers(&self) -> Modifiers {
+        self.modifiers
+            .iter()
+            .fold(Modifiers::empty(), |acc, modifier| match modifier {
+                Modifier::Ctrl => acc | Modifiers::CTRL,
+                Modifier::Alt => acc | Modifiers::ALT,
+                Modifier::Shift => acc | Modifiers::SHIFT,
+                Modifier::Super => acc | Modifiers::LOGO,
+            })
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for modifier in &self.modifiers {
+            write!(f, "{modifier:?}+")?;
+        }
+        f.write_str(&self.key)
+    }
+}
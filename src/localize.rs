@@ -32,6 +32,18 @@ macro_rules! fl {
     }};
 }
 
+/// Whether the preferred language of the user is written from right to left.
+pub static RIGHT_TO_LEFT: Lazy<bool> = Lazy::new(|| {
+    i18n_embed::DesktopLanguageRequester::requested_languages()
+        .first()
+        .map_or(false, |language| {
+            matches!(
+                language.language.as_str(),
+                "ar" | "ckb" | "dv" | "fa" | "he" | "ps" | "sd" | "ug" | "ur" | "yi"
+            )
+        })
+});
+
 // Get the `Localizer` to be used for localizing this library.
 pub fn localizer() -> Box<dyn Localizer> {
     Box::from(DefaultLocalizer::new(&*LANGUAGE_LOADER, &Localizations))
@@ -2,8 +2,20 @@ mod components;
 #[rustfmt::skip]
 mod config;
 mod app;
+mod emoji;
+mod fold;
+mod highlight;
+mod history;
+mod keybinds;
 mod localize;
+mod markup;
+mod providers;
+mod recent;
+mod slots;
+mod store;
 mod subscriptions;
+mod usage;
+mod workspaces;
 use tracing::info;
 
 use localize::localize;
@@ -0,0 +1,84 @@
+//! A minimal subset of Pango markup for result descriptions: bold and italic text.
+//!
+//! Any other tag is dropped, so that plugins can't change how the launcher is drawn beyond
+//! emphasizing parts of a description.
+
+use unicode_truncate::UnicodeTruncateStr;
+use unicode_width::UnicodeWidthStr;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Span {
+    pub text: String,
+    pub bold: bool,
+    pub italic: bool,
+}
+
+/// Splits `line` into spans of equally styled text, with entities decoded.
+pub fn parse(line: &str) -> Vec<Span> {
+    let mut spans = Vec::new();
+    let mut bold = 0u32;
+    let mut italic = 0u32;
+    let mut rest = line;
+
+    while !rest.is_empty() {
+        let (text, tag) = match rest.find('<') {
+            Some(start) => {
+                let Some(len) = rest[start..].find('>') else {
+                    // Not a tag, but a lone `<`.
+                    push(&mut spans, rest, bold > 0, italic > 0);
+                    break;
+                };
+                let tag = &rest[start + 1..start + len];
+                let text = &rest[..start];
+                rest = &rest[start + len + 1..];
+                (text, Some(tag))
+            }
+            None => (std::mem::take(&mut rest), None),
+        };
+
+        push(&mut spans, text, bold > 0, italic > 0);
+        match tag.map(|tag| tag.trim().to_ascii_lowercase()).as_deref() {
+            Some("b" | "strong") => bold += 1,
+            Some("/b" | "/strong") => bold = bold.saturating_sub(1),
+            Some("i" | "em") => italic += 1,
+            Some("/i" | "/em") => italic = italic.saturating_sub(1),
+            _ => {}
+        }
+    }
+
+    spans
+}
+
+/// Cuts the spans to at most `max_width` columns, ending them with an ellipsis if cut.
+pub fn truncate(spans: &mut Vec<Span>, max_width: usize) {
+    let mut width = 0;
+    for i in 0..spans.len() {
+        let span_width = spans[i].text.width();
+        if width + span_width > max_width {
+            spans[i].text = format!("{}...", spans[i].text.unicode_truncate(max_width - width).0);
+            spans.truncate(i + 1);
+            return;
+        }
+        width += span_width;
+    }
+}
+
+fn push(spans: &mut Vec<Span>, text: &str, bold: bool, italic: bool) {
+    if text.is_empty() {
+        return;
+    }
+
+    let text = decode_entities(text);
+    match spans.last_mut() {
+        Some(last) if last.bold == bold && last.italic == italic => last.text.push_str(&text),
+        _ => spans.push(Span { text, bold, italic }),
+    }
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
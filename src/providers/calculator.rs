@@ -0,0 +1,131 @@
+use std::{iter::Peekable, str::Chars};
+
+use super::{SyntheticAction, SyntheticProvider, SyntheticResult};
+use crate::fl;
+
+/// Evaluates arithmetic queries such as `2 * (3 + 4)`, offering to copy the result.
+pub struct Calculator;
+
+impl SyntheticProvider for Calculator {
+    fn results(&self, query: &str) -> Vec<SyntheticResult> {
+        // Plain numbers are left to the other results.
+        if !query.contains(['+', '-', '*', '/', '%', '^', '(']) {
+            return Vec::new();
+        }
+        let Some(value) = evaluate(query) else {
+            return Vec::new();
+        };
+
+        let value = format_number(value);
+        vec![SyntheticResult {
+            name: format!("= {value}"),
+            description: query.to_string(),
+            icon: Some(String::from("accessories-calculator")),
+            action: SyntheticAction::Copy(value),
+        }]
+    }
+
+    fn priority(&self) -> i32 {
+        50
+    }
+
+    fn source(&self) -> Option<String> {
+        Some(fl!("source-calculator"))
+    }
+}
+
+/// Evaluates the expression, if it is one and its result is finite.
+fn evaluate(expression: &str) -> Option<f64> {
+    let mut parser = Parser {
+        chars: expression.chars().peekable(),
+    };
+    let value = parser.expression()?;
+    parser.skip_whitespace();
+    (parser.chars.peek().is_none() && value.is_finite()).then_some(value)
+}
+
+#[allow(clippy::cast_possible_truncation)]
+pub(super) fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{}", value as i64)
+    } else {
+        let formatted = format!("{value:.10}");
+        formatted
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string()
+    }
+}
+
+/// Recursive descent parser of `+ - * / % ^` and parentheses, with the usual precedence.
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+
+    fn next_operator(&mut self, operators: &[char]) -> Option<char> {
+        self.skip_whitespace();
+        self.chars.next_if(|c| operators.contains(c))
+    }
+
+    fn expression(&mut self) -> Option<f64> {
+        let mut value = self.term()?;
+        while let Some(operator) = self.next_operator(&['+', '-']) {
+            let rhs = self.term()?;
+            value = if operator == '+' {
+                value + rhs
+            } else {
+                value - rhs
+            };
+        }
+        Some(value)
+    }
+
+    fn term(&mut self) -> Option<f64> {
+        let mut value = self.power()?;
+        while let Some(operator) = self.next_operator(&['*', '/', '%']) {
+            let rhs = self.power()?;
+            value = match operator {
+                '*' => value * rhs,
+                '/' => value / rhs,
+                _ => value % rhs,
+            };
+        }
+        Some(value)
+    }
+
+    fn power(&mut self) -> Option<f64> {
+        let base = self.unary()?;
+        if self.next_operator(&['^']).is_some() {
+            // Right associative, so that 2^3^2 is 2^9.
+            return Some(base.powf(self.power()?));
+        }
+        Some(base)
+    }
+
+    fn unary(&mut self) -> Option<f64> {
+        if self.next_operator(&['-']).is_some() {
+            return Some(-self.unary()?);
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Option<f64> {
+        self.skip_whitespace();
+        if self.chars.next_if_eq(&'(').is_some() {
+            let value = self.expression()?;
+            self.skip_whitespace();
+            return self.chars.next_if_eq(&')').map(|_| value);
+        }
+
+        let mut number = String::new();
+        while let Some(c) = self.chars.next_if(|c| c.is_ascii_digit() || *c == '.') {
+            number.push(c);
+        }
+        number.parse().ok()
+    }
+}
@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+
+use super::{SyntheticAction, SyntheticProvider, SyntheticResult};
+use crate::{
+    fl, fold,
+    keybinds::{Action, KeyBinding},
+};
+
+/// Prefix of queries searching the commands of the launcher.
+const PREFIX: char = '>';
+
+/// Offers the actions of the launcher itself for queries starting with `>`.
+pub struct CommandPalette {
+    /// The configured keybindings, shown next to the commands they trigger.
+    pub keybindings: HashMap<KeyBinding, Action>,
+}
+
+impl CommandPalette {
+    fn commands() -> [(String, Action, &'static str); 6] {
+        [
+            (
+                fl!("command-toggle-recents"),
+                Action::ToggleRecents,
+                "document-open-recent-symbolic",
+            ),
+            (
+                fl!("command-toggle-sticky"),
+                Action::ToggleSticky,
+                "view-pin-symbolic",
+            ),
+            (
+                fl!("command-toggle-hidden"),
+                Action::ToggleHidden,
+                "view-reveal-symbolic",
+            ),
+            (fl!("command-zoom-in"), Action::ZoomIn, "zoom-in-symbolic"),
+            (
+                fl!("command-zoom-out"),
+                Action::ZoomOut,
+                "zoom-out-symbolic",
+            ),
+            (
+                fl!("command-clear-history"),
+                Action::ClearHistory,
+                "edit-clear-history-symbolic",
+            ),
+        ]
+    }
+}
+
+impl SyntheticProvider for CommandPalette {
+    fn results(&self, query: &str) -> Vec<SyntheticResult> {
+        let Some(query) = query.strip_prefix(PREFIX) else {
+            return Vec::new();
+        };
+
+        let query = fold::normalize(query.trim());
+        Self::commands()
+            .into_iter()
+            .filter(|(name, ..)| fold::normalize(name).contains(&query))
+            .map(|(name, action, icon)| SyntheticResult {
+                description: self
+                    .keybindings
+                    .iter()
+                    .find(|(_, bound)| **bound == action)
+                    .map(|(binding, _)| binding.to_string())
+                    .unwrap_or_default(),
+                name,
+                icon: Some(String::from(icon)),
+                action: SyntheticAction::Launcher(action),
+            })
+            .collect()
+    }
+
+    /// Above everything, as nothing else is looked for with the prefix.
+    fn priority(&self) -> i32 {
+        200
+    }
+
+    fn source(&self) -> Option<String> {
+        Some(fl!("source-commands"))
+    }
+}
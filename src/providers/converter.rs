@@ -0,0 +1,315 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
+
+use serde::Deserialize;
+
+use super::{calculator::format_number, SyntheticAction, SyntheticProvider, SyntheticResult};
+use crate::fl;
+
+const RATES_FILE: &str = "exchange-rates.json";
+/// Age of the cached exchange rates after which they are fetched again.
+const RATES_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+/// Age of the exchange rates after which their results are marked as outdated, in days.
+/// Rates aren't published on weekends and holidays.
+const RATES_STALE_DAYS: i64 = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dimension {
+    Length,
+    Mass,
+    Volume,
+    Time,
+    Data,
+}
+
+/// Names of the units, their dimension and their size in the base unit of the dimension.
+const UNITS: &[(&[&str], Dimension, f64)] = &[
+    (
+        &["m", "meter", "meters", "metre", "metres"],
+        Dimension::Length,
+        1.0,
+    ),
+    (
+        &["km", "kilometer", "kilometers", "kilometre", "kilometres"],
+        Dimension::Length,
+        1e3,
+    ),
+    (
+        &[
+            "cm",
+            "centimeter",
+            "centimeters",
+            "centimetre",
+            "centimetres",
+        ],
+        Dimension::Length,
+        1e-2,
+    ),
+    (
+        &[
+            "mm",
+            "millimeter",
+            "millimeters",
+            "millimetre",
+            "millimetres",
+        ],
+        Dimension::Length,
+        1e-3,
+    ),
+    (&["mi", "mile", "miles"], Dimension::Length, 1609.344),
+    (&["yd", "yard", "yards"], Dimension::Length, 0.9144),
+    (&["ft", "foot", "feet"], Dimension::Length, 0.3048),
+    (&["in", "inch", "inches"], Dimension::Length, 0.0254),
+    (&["nmi"], Dimension::Length, 1852.0),
+    (&["kg", "kilogram", "kilograms"], Dimension::Mass, 1.0),
+    (&["g", "gram", "grams"], Dimension::Mass, 1e-3),
+    (&["mg", "milligram", "milligrams"], Dimension::Mass, 1e-6),
+    (&["t", "tonne", "tonnes"], Dimension::Mass, 1e3),
+    (
+        &["lb", "lbs", "pound", "pounds"],
+        Dimension::Mass,
+        0.453_592_37,
+    ),
+    (
+        &["oz", "ounce", "ounces"],
+        Dimension::Mass,
+        0.028_349_523_125,
+    ),
+    (&["st", "stone"], Dimension::Mass, 6.350_293_18),
+    (
+        &["l", "liter", "liters", "litre", "litres"],
+        Dimension::Volume,
+        1.0,
+    ),
+    (
+        &[
+            "ml",
+            "milliliter",
+            "milliliters",
+            "millilitre",
+            "millilitres",
+        ],
+        Dimension::Volume,
+        1e-3,
+    ),
+    (&["cl"], Dimension::Volume, 1e-2),
+    (
+        &["gal", "gallon", "gallons"],
+        Dimension::Volume,
+        3.785_411_784,
+    ),
+    (&["qt", "quart", "quarts"], Dimension::Volume, 0.946_352_946),
+    (&["pt", "pint", "pints"], Dimension::Volume, 0.473_176_473),
+    (&["cup", "cups"], Dimension::Volume, 0.236_588_236_5),
+    (&["floz"], Dimension::Volume, 0.029_573_529_562_5),
+    (&["s", "sec", "second", "seconds"], Dimension::Time, 1.0),
+    (
+        &["ms", "millisecond", "milliseconds"],
+        Dimension::Time,
+        1e-3,
+    ),
+    (&["min", "minute", "minutes"], Dimension::Time, 60.0),
+    (&["h", "hr", "hour", "hours"], Dimension::Time, 3600.0),
+    (&["d", "day", "days"], Dimension::Time, 86400.0),
+    (&["wk", "week", "weeks"], Dimension::Time, 604_800.0),
+    (&["byte", "bytes"], Dimension::Data, 1.0),
+    (&["kb"], Dimension::Data, 1e3),
+    (&["mb"], Dimension::Data, 1e6),
+    (&["gb"], Dimension::Data, 1e9),
+    (&["tb"], Dimension::Data, 1e12),
+    (&["kib"], Dimension::Data, 1024.0),
+    (&["mib"], Dimension::Data, 1_048_576.0),
+    (&["gib"], Dimension::Data, 1_073_741_824.0),
+    (&["tib"], Dimension::Data, 1_099_511_627_776.0),
+];
+
+/// Converts queries such as `10 km in mi` or `100 usd to eur`, offering to copy the result.
+pub struct Converter {
+    /// The cached exchange rates, replaced once fetched again.
+    rates: Arc<Mutex<Option<ExchangeRates>>>,
+}
+
+impl Converter {
+    /// Loads the cached exchange rates, and fetches them again from `rates_url` in the
+    /// background if they are outdated.
+    pub fn new(rates_url: Option<String>) -> Self {
+        let converter = Self {
+            rates: Arc::new(Mutex::new(ExchangeRates::load())),
+        };
+        if let Some(url) = rates_url {
+            refresh_exchange_rates(url, converter.rates.clone());
+        }
+        converter
+    }
+}
+
+impl SyntheticProvider for Converter {
+    fn results(&self, query: &str) -> Vec<SyntheticResult> {
+        let Some((amount, from, to)) = parse(query) else {
+            return Vec::new();
+        };
+
+        let rates = self.rates.lock().unwrap_or_else(PoisonError::into_inner);
+        let (value, description) = if let Some(value) = convert_unit(amount, &from, &to) {
+            (format_number(value), query.to_string())
+        } else if let Some((value, rates)) = rates
+            .as_ref()
+            .and_then(|rates| Some((rates.convert(amount, &from, &to)?, rates)))
+        {
+            let description = if rates.is_stale() {
+                fl!("exchange-rates-stale", date = rates.date.as_str())
+            } else {
+                fl!("exchange-rates", date = rates.date.as_str())
+            };
+            (format!("{value:.2}"), description)
+        } else {
+            return Vec::new();
+        };
+
+        vec![SyntheticResult {
+            name: format!("= {value} {to}"),
+            description,
+            icon: Some(String::from("accessories-calculator")),
+            action: SyntheticAction::Copy(value),
+        }]
+    }
+
+    fn priority(&self) -> i32 {
+        50
+    }
+
+    fn source(&self) -> Option<String> {
+        Some(fl!("source-converter"))
+    }
+}
+
+/// Splits a query of the form `<amount> <unit> to|in <unit>`, with the units lowercased.
+fn parse(query: &str) -> Option<(f64, String, String)> {
+    let query = query.to_lowercase();
+    let (from, to) = query
+        .rsplit_once(" to ")
+        .or_else(|| query.rsplit_once(" in "))?;
+    let unit_start = from.find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-'))?;
+    let amount = from[..unit_start].parse().ok()?;
+    let (from, to) = (from[unit_start..].trim(), to.trim());
+    (!from.is_empty() && !to.is_empty()).then(|| (amount, from.to_string(), to.to_string()))
+}
+
+fn convert_unit(amount: f64, from: &str, to: &str) -> Option<f64> {
+    if let Some(value) = convert_temperature(amount, from, to) {
+        return Some(value);
+    }
+    let unit = |name: &str| UNITS.iter().find(|(names, ..)| names.contains(&name));
+    let (_, from_dimension, from_size) = unit(from)?;
+    let (_, to_dimension, to_size) = unit(to)?;
+    (from_dimension == to_dimension).then(|| amount * from_size / to_size)
+}
+
+fn convert_temperature(amount: f64, from: &str, to: &str) -> Option<f64> {
+    let kelvin = match from.trim_start_matches('°') {
+        "c" | "celsius" => amount + 273.15,
+        "f" | "fahrenheit" => (amount - 32.0) * 5.0 / 9.0 + 273.15,
+        "k" | "kelvin" => amount,
+        _ => return None,
+    };
+    match to.trim_start_matches('°') {
+        "c" | "celsius" => Some(kelvin - 273.15),
+        "f" | "fahrenheit" => Some((kelvin - 273.15) * 9.0 / 5.0 + 32.0),
+        "k" | "kelvin" => Some(kelvin),
+        _ => None,
+    }
+}
+
+/// Exchange rates in the format of the Frankfurter API, relative to `base`.
+#[derive(Debug, Deserialize)]
+struct ExchangeRates {
+    base: String,
+    /// Day the rates were published, e.g. `2024-05-17`.
+    date: String,
+    rates: HashMap<String, f64>,
+}
+
+impl ExchangeRates {
+    fn path() -> Option<PathBuf> {
+        xdg::BaseDirectories::with_prefix("cosmic-launcher")
+            .ok()
+            .map(|dirs| dirs.get_cache_file(RATES_FILE))
+    }
+
+    /// The cached exchange rates, if they were ever fetched.
+    fn load() -> Option<Self> {
+        let data = std::fs::read(Self::path()?).ok()?;
+        serde_json::from_slice(&data).ok()
+    }
+
+    fn rate(&self, currency: &str) -> Option<f64> {
+        let currency = currency.to_uppercase();
+        if currency == self.base {
+            Some(1.0)
+        } else {
+            self.rates.get(&currency).copied()
+        }
+    }
+
+    fn convert(&self, amount: f64, from: &str, to: &str) -> Option<f64> {
+        Some(amount / self.rate(from)? * self.rate(to)?)
+    }
+
+    fn is_stale(&self) -> bool {
+        chrono::NaiveDate::parse_from_str(&self.date, "%Y-%m-%d").map_or(true, |date| {
+            (chrono::Local::now().date_naive() - date).num_days() > RATES_STALE_DAYS
+        })
+    }
+}
+
+/// Fetches the exchange rates from `url` into the cache and `rates` in the background, unless
+/// the cached rates are recent. Failures leave the cached rates in place.
+fn refresh_exchange_rates(url: String, rates: Arc<Mutex<Option<ExchangeRates>>>) {
+    let Some(path) = ExchangeRates::path() else {
+        return;
+    };
+    let fresh = std::fs::metadata(&path)
+        .and_then(|metadata| metadata.modified())
+        .map_or(false, |modified| {
+            modified.elapsed().map_or(false, |age| age < RATES_MAX_AGE)
+        });
+    if fresh {
+        return;
+    }
+
+    std::thread::spawn(move || {
+        let output = match std::process::Command::new("curl")
+            .args(["--fail", "--silent", "--location", "--max-time", "10", &url])
+            .output()
+        {
+            Ok(output) if output.status.success() => output.stdout,
+            Ok(output) => {
+                tracing::warn!("failed to fetch exchange rates: {}", output.status);
+                return;
+            }
+            Err(err) => {
+                tracing::warn!("failed to run curl to fetch exchange rates: {err}");
+                return;
+            }
+        };
+        let fetched = match serde_json::from_slice::<ExchangeRates>(&output) {
+            Ok(fetched) => fetched,
+            Err(err) => {
+                tracing::warn!("failed to parse exchange rates from {url}: {err}");
+                return;
+            }
+        };
+        *rates.lock().unwrap_or_else(PoisonError::into_inner) = Some(fetched);
+        let saved = xdg::BaseDirectories::with_prefix("cosmic-launcher")
+            .map_err(std::io::Error::other)
+            .and_then(|dirs| dirs.place_cache_file(RATES_FILE))
+            .and_then(|path| std::fs::write(path, output));
+        if let Err(err) = saved {
+            tracing::error!("failed to save exchange rates: {err}");
+        }
+    });
+}
@@ -0,0 +1,66 @@
+use std::collections::HashSet;
+
+use cosmic::desktop::{DesktopEntryData, IconSource};
+
+use super::{SyntheticAction, SyntheticProvider, SyntheticResult};
+use crate::{app::ResultKind, fl, fold};
+
+/// Apps whose desktop entries are hidden with `NoDisplay`, which pop-launcher never returns.
+pub struct HiddenApps {
+    entries: Vec<DesktopEntryData>,
+}
+
+impl HiddenApps {
+    pub fn load() -> Self {
+        let shown: HashSet<_> = cosmic::desktop::load_applications(None, false)
+            .into_iter()
+            .map(|entry| entry.id)
+            .collect();
+        let entries = cosmic::desktop::load_applications(None, true)
+            .into_iter()
+            .filter(|entry| entry.exec.is_some() && !shown.contains(&entry.id))
+            .collect();
+        Self { entries }
+    }
+}
+
+impl SyntheticProvider for HiddenApps {
+    fn results(&self, query: &str) -> Vec<SyntheticResult> {
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let query = fold::normalize(query);
+        self.entries
+            .iter()
+            .filter(|entry| {
+                fold::normalize(&entry.name).contains(&query)
+                    || fold::normalize(&entry.id).contains(&query)
+            })
+            .filter_map(|entry| {
+                Some(SyntheticResult {
+                    name: entry.name.clone(),
+                    description: fl!("hidden-entry", id = entry.id.as_str()),
+                    icon: match &entry.icon {
+                        IconSource::Name(name) => Some(name.clone()),
+                        _ => None,
+                    },
+                    action: SyntheticAction::Run(entry.exec.clone()?),
+                })
+            })
+            .collect()
+    }
+
+    /// Below the apps which aren't hidden.
+    fn priority(&self) -> i32 {
+        -10
+    }
+
+    fn kind(&self) -> Option<ResultKind> {
+        Some(ResultKind::App)
+    }
+
+    fn source(&self) -> Option<String> {
+        Some(fl!("source-hidden"))
+    }
+}
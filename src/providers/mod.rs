@@ -0,0 +1,95 @@
+//! Results provided by the launcher itself, merged with the results of pop-launcher.
+
+mod calculator;
+mod commands;
+mod converter;
+mod hidden_apps;
+mod scratchpad;
+mod web_search;
+
+use std::rc::Rc;
+
+use crate::{app::ResultKind, config::Config, keybinds::Action};
+
+pub use calculator::Calculator;
+pub use commands::CommandPalette;
+pub use converter::Converter;
+pub use hidden_apps::HiddenApps;
+pub use web_search::WebSearch;
+
+/// A result provided by the launcher instead of pop-launcher.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntheticResult {
+    pub name: String,
+    pub description: String,
+    /// Name of the icon shown for the result.
+    pub icon: Option<String>,
+    pub action: SyntheticAction,
+}
+
+/// What activating a synthetic result does.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyntheticAction {
+    /// Run the command line.
+    Run(String),
+    /// Copy the text to the clipboard.
+    Copy(String),
+    /// Open the URL with the default handler for its scheme.
+    OpenUrl(String),
+    /// Perform an action of the launcher itself.
+    Launcher(Action),
+}
+
+/// A source of results besides pop-launcher.
+pub trait SyntheticProvider {
+    /// The results for the trimmed query, if any.
+    fn results(&self, query: &str) -> Vec<SyntheticResult>;
+
+    /// Results of providers with a positive priority are shown above the results of
+    /// pop-launcher, and the others below them, in the order of their priorities.
+    fn priority(&self) -> i32;
+
+    /// The kind of the results, for the type filter.
+    fn kind(&self) -> Option<ResultKind> {
+        None
+    }
+
+    /// Name of the provider shown in the source badge of its results, instead of their kind.
+    fn source(&self) -> Option<String> {
+        None
+    }
+}
+
+/// The unit converter, if enabled in the config. Kept across config changes which don't
+/// affect it, as creating it reloads the exchange rates and may fetch them again.
+pub fn converter(config: &Config) -> Option<Rc<Converter>> {
+    config
+        .unit_conversion
+        .then(|| Rc::new(Converter::new(config.exchange_rates_url.clone())))
+}
+
+/// The built-in providers enabled in the config, with the converter created for it.
+pub fn builtin(
+    config: &Config,
+    converter: Option<&Rc<Converter>>,
+) -> Vec<Rc<dyn SyntheticProvider>> {
+    let mut providers: Vec<Rc<dyn SyntheticProvider>> = Vec::new();
+    if let Some(scratchpad) = &config.scratchpad {
+        providers.push(Rc::new(scratchpad.clone()));
+    }
+    if config.command_palette {
+        providers.push(Rc::new(CommandPalette {
+            keybindings: config.keybindings.clone(),
+        }));
+    }
+    if config.calculator {
+        providers.push(Rc::new(Calculator));
+    }
+    if let Some(converter) = converter {
+        providers.push(converter.clone());
+    }
+    if let Some(url) = &config.web_search {
+        providers.push(Rc::new(WebSearch { url: url.clone() }));
+    }
+    providers
+}
@@ -0,0 +1,26 @@
+use super::{SyntheticAction, SyntheticProvider, SyntheticResult};
+use crate::{config::Scratchpad, fl};
+
+impl SyntheticProvider for Scratchpad {
+    fn results(&self, query: &str) -> Vec<SyntheticResult> {
+        if !self.matches(query) {
+            return Vec::new();
+        }
+
+        vec![SyntheticResult {
+            name: self.name.clone(),
+            description: self.command.clone(),
+            icon: Some(String::from("accessories-text-editor")),
+            action: SyntheticAction::Run(self.command.clone()),
+        }]
+    }
+
+    /// Pinned above everything else.
+    fn priority(&self) -> i32 {
+        100
+    }
+
+    fn source(&self) -> Option<String> {
+        Some(fl!("source-scratchpad"))
+    }
+}
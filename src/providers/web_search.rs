@@ -0,0 +1,46 @@
+use std::fmt::Write;
+
+use super::{SyntheticAction, SyntheticProvider, SyntheticResult};
+use crate::{app::ResultKind, fl};
+
+/// Offers to search the web for any query, below the other results.
+pub struct WebSearch {
+    /// URL of the search, in which `{query}` is replaced with the query.
+    pub url: String,
+}
+
+impl SyntheticProvider for WebSearch {
+    fn results(&self, query: &str) -> Vec<SyntheticResult> {
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let url = self.url.replace("{query}", &percent_encode(query));
+        vec![SyntheticResult {
+            name: fl!("search-web", query = query),
+            description: url.clone(),
+            icon: Some(String::from("web-browser")),
+            action: SyntheticAction::OpenUrl(url),
+        }]
+    }
+
+    fn priority(&self) -> i32 {
+        -100
+    }
+
+    fn kind(&self) -> Option<ResultKind> {
+        Some(ResultKind::Web)
+    }
+}
+
+/// Encodes everything but the unreserved characters of URLs.
+fn percent_encode(text: &str) -> String {
+    text.bytes().fold(String::new(), |mut encoded, byte| {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            encoded.push(char::from(byte));
+        } else {
+            _ = write!(encoded, "%{byte:02X}");
+        }
+        encoded
+    })
+}
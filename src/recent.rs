@@ -0,0 +1,109 @@
+use chrono::{DateTime, Utc};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use crate::fl;
+
+/// When files were last used, from the XDG recently-used bookmarks of GTK applications.
+#[derive(Debug, Default, Clone)]
+pub struct RecentFiles {
+    used: HashMap<PathBuf, DateTime<Utc>>,
+}
+
+impl RecentFiles {
+    pub fn load() -> Self {
+        let Ok(dirs) = xdg::BaseDirectories::new() else {
+            return Self::default();
+        };
+        let Some(path) = dirs.find_data_file("recently-used.xbel") else {
+            return Self::default();
+        };
+
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => Self::parse(&contents),
+            Err(err) => {
+                tracing::error!("failed to read {}: {err}", path.display());
+                Self::default()
+            }
+        }
+    }
+
+    /// Reads the `bookmark` elements of the file, which are all that is needed from it.
+    fn parse(contents: &str) -> Self {
+        let mut used = HashMap::new();
+        for bookmark in contents.split("<bookmark ").skip(1) {
+            let tag = bookmark.split('>').next().unwrap_or_default();
+            let mut href = None;
+            let mut last_used = None;
+            for (name, value) in tag.split_whitespace().filter_map(attribute) {
+                match name {
+                    "href" => href = decode_file_uri(value),
+                    "modified" | "visited" => {
+                        let Ok(time) = DateTime::parse_from_rfc3339(value) else {
+                            continue;
+                        };
+                        let time = time.with_timezone(&Utc);
+                        last_used = last_used.max(Some(time));
+                    }
+                    _ => {}
+                }
+            }
+
+            if let Some((path, time)) = href.zip(last_used) {
+                used.insert(path, time);
+            }
+        }
+
+        Self { used }
+    }
+
+    pub fn last_used(&self, path: &Path) -> Option<DateTime<Utc>> {
+        self.used.get(path).copied()
+    }
+}
+
+/// Splits `name="value"`, the value without quotes.
+fn attribute(token: &str) -> Option<(&str, &str)> {
+    let (name, value) = token.split_once('=')?;
+    Some((name, value.trim_end_matches('/').trim_matches('"')))
+}
+
+/// Decodes a percent-encoded `file://` URI, which may also contain XML entities.
+fn decode_file_uri(uri: &str) -> Option<PathBuf> {
+    let uri = uri.replace("&amp;", "&");
+    let encoded = uri.strip_prefix("file://")?.as_bytes();
+    let mut bytes = Vec::with_capacity(encoded.len());
+    let mut i = 0;
+    while i < encoded.len() {
+        let hex = encoded
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok());
+        match hex.filter(|_| encoded[i] == b'%') {
+            Some(hex) => {
+                bytes.push(u8::from_str_radix(hex, 16).ok()?);
+                i += 3;
+            }
+            None => {
+                bytes.push(encoded[i]);
+                i += 1;
+            }
+        }
+    }
+    Some(PathBuf::from(String::from_utf8(bytes).ok()?))
+}
+
+/// Formats how long ago a file was used, e.g. "Opened 2 hours ago".
+pub fn used_ago(time: DateTime<Utc>) -> String {
+    let seconds = (Utc::now() - time).num_seconds().max(0);
+    if seconds < 60 {
+        fl!("opened-just-now")
+    } else if seconds < 60 * 60 {
+        fl!("opened-minutes-ago", minutes = seconds / 60)
+    } else if seconds < 24 * 60 * 60 {
+        fl!("opened-hours-ago", hours = seconds / (60 * 60))
+    } else {
+        fl!("opened-days-ago", days = seconds / (24 * 60 * 60))
+    }
+}
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::store;
+
+const SLOTS_FILE: &str = "slots.json";
+
+/// Apps assigned to the quick slots, kept apart from the usage store so that clearing the
+/// launch history doesn't unassign them.
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct QuickSlots {
+    /// Desktop ids of the assigned apps, by slot number.
+    pub slots: BTreeMap<u8, String>,
+}
+
+impl QuickSlots {
+    pub fn load() -> Self {
+        store::load_json(SLOTS_FILE)
+    }
+
+    pub fn save(&self) {
+        if let Err(err) = store::save_json(SLOTS_FILE, self) {
+            tracing::error!("failed to save quick slots: {err}");
+        }
+    }
+
+    /// The slot which `desktop_id` is assigned to, if any.
+    pub fn slot_of(&self, desktop_id: &str) -> Option<u8> {
+        self.slots
+            .iter()
+            .find_map(|(slot, id)| (id == desktop_id).then_some(*slot))
+    }
+}
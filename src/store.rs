@@ -0,0 +1,34 @@
+//! JSON files of the stores kept in the launcher's data dir.
+
+use serde::{de::DeserializeOwned, Serialize};
+use std::path::PathBuf;
+
+/// Path of the data file `name`, whether it exists or not.
+pub fn data_path(name: &str) -> Option<PathBuf> {
+    xdg::BaseDirectories::with_prefix("cosmic-launcher")
+        .ok()
+        .map(|dirs| dirs.get_data_file(name))
+}
+
+/// Reads the data file `name`, or the default if it is missing or can't be parsed.
+pub fn load_json<T: DeserializeOwned + Default>(name: &str) -> T {
+    let Some(path) = data_path(name) else {
+        return T::default();
+    };
+
+    match std::fs::read(&path) {
+        Ok(data) => serde_json::from_slice(&data).unwrap_or_else(|err| {
+            tracing::error!("failed to parse {}: {err}", path.display());
+            T::default()
+        }),
+        Err(_) => T::default(),
+    }
+}
+
+/// Writes `value` to the data file `name`, creating the data dir if needed.
+pub fn save_json<T: Serialize>(name: &str, value: &T) -> std::io::Result<()> {
+    let dirs =
+        xdg::BaseDirectories::with_prefix("cosmic-launcher").map_err(std::io::Error::other)?;
+    let path = dirs.place_data_file(name)?;
+    std::fs::write(path, serde_json::to_vec(value)?)
+}
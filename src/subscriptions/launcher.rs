@@ -1,7 +1,13 @@
 use cosmic::{iced::futures::StreamExt, iced_runtime::futures::MaybeSend};
 use futures::{SinkExt, Stream};
 use pop_launcher_service::IpcClient;
-use std::hash::Hash;
+use std::{
+    hash::Hash,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
 use tokio::sync::{mpsc, oneshot};
 
 #[derive(Debug, Clone)]
@@ -37,14 +43,19 @@ pub fn subscription<I: 'static + Hash + Copy + Send + Sync>(
 }
 
 /// Initializes pop-launcher if it is not running, and returns a handle to its client.
+///
+/// Responses of the instance are tagged with the current `session`, so that responses which
+/// arrive after the instance was closed can be told apart from those of its successor.
 async fn client_request<'a>(
-    tx: &mpsc::Sender<Event>,
+    tx: &mpsc::Sender<(u64, Event)>,
+    session: &AtomicU64,
     client: &'a mut Option<(IpcClient, oneshot::Sender<()>)>,
 ) -> &'a mut Option<(IpcClient, oneshot::Sender<()>)> {
     if client.is_none() {
         *client = match pop_launcher_service::IpcClient::new() {
             Ok((mut new_client, responses)) => {
                 let tx = tx.clone();
+                let session = session.load(Ordering::SeqCst);
 
                 let (kill_tx, kill_rx) = tokio::sync::oneshot::channel();
                 let listener = async {
@@ -52,7 +63,7 @@ async fn client_request<'a>(
                     let listener = Box::pin(async move {
                         let mut responses = std::pin::pin!(responses);
                         while let Some(response) = responses.next().await {
-                            let _res = tx.send(Event::Response(response)).await;
+                            let _res = tx.send((session, Event::Response(response))).await;
                         }
                     });
 
@@ -90,37 +101,50 @@ async fn client_request<'a>(
 pub fn service() -> impl Stream<Item = Event> + MaybeSend {
     let (requests_tx, mut requests_rx) = mpsc::channel(4);
     let (responses_tx, mut responses_rx) = mpsc::channel(4);
+    // Incremented whenever the pop-launcher instance is closed.
+    let session = Arc::new(AtomicU64::new(0));
+    let current_session = session.clone();
 
     let service_future = async move {
-        let _res = responses_tx.send(Event::Started(requests_tx.clone())).await;
+        let _res = responses_tx
+            .send((
+                session.load(Ordering::SeqCst),
+                Event::Started(requests_tx.clone()),
+            ))
+            .await;
 
         let client = &mut None;
 
         while let Some(request) = requests_rx.recv().await {
             match request {
                 Request::Search(s) => {
-                    if let Some((client, _)) = client_request(&responses_tx, client).await {
+                    if let Some((client, _)) = client_request(&responses_tx, &session, client).await
+                    {
                         let _res = client.send(pop_launcher::Request::Search(s)).await;
                     }
                 }
                 Request::Activate(i) => {
-                    if let Some((client, _)) = client_request(&responses_tx, client).await {
+                    if let Some((client, _)) = client_request(&responses_tx, &session, client).await
+                    {
                         let _res = client.send(pop_launcher::Request::Activate(i)).await;
                     }
                 }
                 Request::Context(i) => {
-                    if let Some((client, _)) = client_request(&responses_tx, client).await {
+                    if let Some((client, _)) = client_request(&responses_tx, &session, client).await
+                    {
                         let _res = client.send(pop_launcher::Request::Context(i)).await;
                     }
                 }
                 Request::ActivateContext(id, context) => {
-                    if let Some((client, _)) = client_request(&responses_tx, client).await {
+                    if let Some((client, _)) = client_request(&responses_tx, &session, client).await
+                    {
                         let _res = client
                             .send(pop_launcher::Request::ActivateContext { id, context })
                             .await;
                     }
                 }
                 Request::Close => {
+                    session.fetch_add(1, Ordering::SeqCst);
                     if let Some((mut client, kill)) = client.take() {
                         tracing::info!("closing pop-launcher instance");
                         let _res = kill.send(());
@@ -129,7 +153,8 @@ pub fn service() -> impl Stream<Item = Event> + MaybeSend {
                     }
                 }
                 Request::Complete(id) => {
-                    if let Some((client, _)) = client_request(&responses_tx, client).await {
+                    if let Some((client, _)) = client_request(&responses_tx, &session, client).await
+                    {
                         let _res = client.send(pop_launcher::Request::Complete(id)).await;
                     }
                 }
@@ -146,7 +171,13 @@ pub fn service() -> impl Stream<Item = Event> + MaybeSend {
     let _res = tokio::task::spawn(service_future);
 
     async_stream::stream! {
-        while let Some(message) = responses_rx.recv().await {
+        while let Some((session, message)) = responses_rx.recv().await {
+            // Late responses of a closed instance, e.g. the results of a search made before
+            // the launcher was hidden, would otherwise show up when it is opened next.
+            if session != current_session.load(Ordering::SeqCst) {
+                tracing::debug!("dropping response of closed pop-launcher instance");
+                continue;
+            }
             yield message;
         }
     }
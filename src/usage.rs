@@ -0,0 +1,107 @@
+use pop_launcher::GpuPreference;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{config::FrecencyWeights, store};
+
+const USAGE_FILE: &str = "usage.json";
+
+/// Launch history of applications started from the launcher, kept locally in the user's data dir.
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct UsageStore {
+    pub apps: HashMap<String, AppUsage>,
+    #[serde(default)]
+    pub last_launch: Option<LastLaunch>,
+    /// Name of the result last activated for each query, for `learn_selections`.
+    #[serde(default)]
+    pub selections: HashMap<String, String>,
+}
+
+#[derive(Debug, Default, Clone, Copy, Deserialize, Serialize)]
+pub struct AppUsage {
+    pub launches: u32,
+    /// Seconds since the Unix epoch.
+    pub last_launched: u64,
+}
+
+/// The most recent launch, kept so that it can be repeated.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LastLaunch {
+    pub app_id: String,
+    pub exec: String,
+    pub gpu_preference: GpuPreference,
+}
+
+impl UsageStore {
+    pub fn load() -> Self {
+        store::load_json(USAGE_FILE)
+    }
+
+    pub fn save(&self) {
+        if let Err(err) = store::save_json(USAGE_FILE, self) {
+            tracing::error!("failed to save launcher usage: {err}");
+        }
+    }
+
+    /// Forgets the launches, keeping the learned selections.
+    pub fn clear_launches(&mut self) {
+        self.apps.clear();
+        self.last_launch = None;
+    }
+
+    pub fn record(&mut self, app_id: &str) {
+        let usage = self.apps.entry(app_id.to_string()).or_default();
+        usage.launches = usage.launches.saturating_add(1);
+        usage.last_launched = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+    }
+
+    /// Scores an app by how often and how recently it was launched.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn frecency(&self, app_id: &str, weights: FrecencyWeights) -> f32 {
+        let Some(usage) = self.apps.get(app_id) else {
+            return 0.0;
+        };
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        let days = now.saturating_sub(usage.last_launched) as f32 / (24.0 * 60.0 * 60.0);
+        weights.frequency * (usage.launches as f32).ln_1p() + weights.recency / (1.0 + days)
+    }
+
+    /// Apps ordered from the most to the least launched.
+    pub fn ranking(&self) -> Vec<(&str, AppUsage)> {
+        let mut ranking: Vec<_> = self
+            .apps
+            .iter()
+            .map(|(app_id, usage)| (app_id.as_str(), *usage))
+            .collect();
+        ranking.sort_by(|a, b| b.1.launches.cmp(&a.1.launches).then(a.0.cmp(b.0)));
+        ranking
+    }
+}
+
+/// Deletes the stored launch history and search history, keeping the learned selections.
+pub fn clear() -> std::io::Result<()> {
+    let mut store = UsageStore::load();
+    store.clear_launches();
+    store::save_json(USAGE_FILE, &store)?;
+    crate::history::clear()
+}
+
+/// Prints the launch ranking for the `stats` subcommand.
+pub fn print_stats() {
+    let store = UsageStore::load();
+    if store.apps.is_empty() {
+        println!("No applications have been launched yet.");
+        return;
+    }
+
+    for (app_id, usage) in store.ranking() {
+        println!("{:>6}  {app_id}", usage.launches);
+    }
+}
@@ -0,0 +1,324 @@
+use cosmic::cctk::{
+    self,
+    cosmic_protocols::{
+        toplevel_info::v1::client::zcosmic_toplevel_handle_v1::{self, ZcosmicToplevelHandleV1},
+        toplevel_management::v1::client::zcosmic_toplevel_manager_v1,
+        workspace::v1::client::{
+            zcosmic_workspace_group_handle_v1::ZcosmicWorkspaceGroupCapabilitiesV1,
+            zcosmic_workspace_handle_v1::{self, ZcosmicWorkspaceHandleV1},
+        },
+    },
+    sctk::{
+        self,
+        output::{OutputHandler, OutputState},
+        reexports::{
+            calloop::{channel, EventLoop},
+            calloop_wayland_source::WaylandSource,
+            client::{
+                globals::registry_queue_init, protocol::wl_output::WlOutput, Connection, Proxy,
+                QueueHandle, WEnum,
+            },
+        },
+        registry::{ProvidesRegistryState, RegistryState},
+    },
+    toplevel_info::{ToplevelInfoHandler, ToplevelInfoState},
+    toplevel_management::{ToplevelManagerHandler, ToplevelManagerState},
+    workspace::{WorkspaceGroup, WorkspaceHandler, WorkspaceState},
+};
+
+/// What to open on a new workspace.
+#[derive(Debug, Clone)]
+pub enum Target {
+    /// An app about to be launched, which opens on the workspace switched to.
+    App,
+    /// The window with the given title, moved to the new workspace.
+    Window(String),
+}
+
+/// Client of the compositor's workspaces, on a Wayland connection and thread of its own, as
+/// the launcher's surfaces don't expose theirs.
+pub struct Workspaces(channel::Sender<Target>);
+
+impl Workspaces {
+    /// Connects to the compositor, if it lets clients manage workspaces and windows.
+    pub fn spawn() -> Option<Self> {
+        let (tx, rx) = channel::channel();
+        let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            if let Err(err) = run(rx, &ready_tx) {
+                tracing::warn!("workspace management unavailable: {err}");
+                let _ = ready_tx.send(false);
+            }
+        });
+        ready_rx.recv().unwrap_or(false).then_some(Self(tx))
+    }
+
+    /// Switches to a new workspace, moving the window of `target` there.
+    pub fn open_on_new_workspace(&self, target: Target) {
+        if self.0.send(target).is_err() {
+            tracing::error!("workspace management stopped");
+        }
+    }
+}
+
+fn run(
+    rx: channel::Channel<Target>,
+    ready: &std::sync::mpsc::Sender<bool>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let conn = Connection::connect_to_env()?;
+    let (globals, event_queue) = registry_queue_init::<State>(&conn)?;
+    let qh = event_queue.handle();
+    let mut event_loop = EventLoop::<State>::try_new()?;
+    WaylandSource::new(conn.clone(), event_queue)
+        .insert(event_loop.handle())
+        .map_err(|err| err.error)?;
+    event_loop
+        .handle()
+        .insert_source(rx, |event, _, state| {
+            if let channel::Event::Msg(target) = event {
+                state.pending = Some(Pending {
+                    target,
+                    created: false,
+                });
+                state.open();
+            }
+        })
+        .map_err(|err| err.error)?;
+
+    let registry_state = RegistryState::new(&globals);
+    let mut state = State {
+        conn,
+        output_state: OutputState::new(&globals, &qh),
+        workspace_state: WorkspaceState::new(&registry_state, &qh),
+        toplevel_info_state: ToplevelInfoState::new(&registry_state, &qh),
+        toplevel_manager_state: ToplevelManagerState::new(&registry_state, &qh),
+        registry_state,
+        pending: None,
+    };
+    let available = state.workspace_state.workspace_manager().get().is_ok()
+        && state.toplevel_manager_state.manager.version() >= 2;
+    let _ = ready.send(available);
+    if !available {
+        return Ok(());
+    }
+
+    loop {
+        event_loop.dispatch(None, &mut state)?;
+    }
+}
+
+/// A target waiting for the workspace state, or for the workspace created for it.
+struct Pending {
+    target: Target,
+    created: bool,
+}
+
+struct State {
+    conn: Connection,
+    registry_state: RegistryState,
+    output_state: OutputState,
+    workspace_state: WorkspaceState,
+    toplevel_info_state: ToplevelInfoState,
+    toplevel_manager_state: ToplevelManagerState,
+    pending: Option<Pending>,
+}
+
+impl State {
+    /// The window with the given title, and an output it is on.
+    fn window(&self, title: &str) -> Option<(&ZcosmicToplevelHandleV1, Option<&WlOutput>)> {
+        self.toplevel_info_state
+            .toplevels()
+            .find_map(|(handle, info)| {
+                let info = info.filter(|info| info.title == title)?;
+                Some((handle, info.output.iter().next()))
+            })
+    }
+
+    /// The workspace group the window is on, or that of the focused window otherwise.
+    fn group(&self, window: Option<&ZcosmicToplevelHandleV1>) -> Option<&WorkspaceGroup> {
+        let focused = || {
+            self.toplevel_info_state
+                .toplevels()
+                .find_map(|(handle, info)| {
+                    info.filter(|info| {
+                        info.state
+                            .contains(&zcosmic_toplevel_handle_v1::State::Activated)
+                    })
+                    .map(|_| handle)
+                })
+        };
+        let workspaces = window
+            .or_else(focused)
+            .and_then(|handle| self.toplevel_info_state.info(handle))
+            .map(|info| &info.workspace);
+        let groups = self.workspace_state.workspace_groups();
+        workspaces
+            .and_then(|workspaces| {
+                groups.iter().find(|group| {
+                    group
+                        .workspaces
+                        .iter()
+                        .any(|workspace| workspaces.contains(&workspace.handle))
+                })
+            })
+            .or_else(|| {
+                groups.iter().find(|group| {
+                    group.workspaces.iter().any(|workspace| {
+                        workspace
+                            .state
+                            .contains(&WEnum::Value(zcosmic_workspace_handle_v1::State::Active))
+                    })
+                })
+            })
+    }
+
+    /// The last workspace of the group which has no windows, as cosmic-comp keeps one at the
+    /// end of each group.
+    fn empty_workspace(&self, group: &WorkspaceGroup) -> Option<ZcosmicWorkspaceHandleV1> {
+        group
+            .workspaces
+            .iter()
+            .filter(|workspace| {
+                !self
+                    .toplevel_info_state
+                    .toplevels()
+                    .filter_map(|(_, info)| info)
+                    .any(|info| info.workspace.contains(&workspace.handle))
+            })
+            .max_by(|a, b| a.coordinates.cmp(&b.coordinates))
+            .map(|workspace| workspace.handle.clone())
+    }
+
+    /// Switches to an empty workspace for the pending target, creating one if there is none.
+    fn open(&mut self) {
+        let Some(pending) = self.pending.take() else {
+            return;
+        };
+        let Ok(manager) = self.workspace_state.workspace_manager().get() else {
+            return;
+        };
+
+        let window = match &pending.target {
+            Target::App => None,
+            Target::Window(title) => {
+                let Some(window) = self.window(title) else {
+                    tracing::warn!("no window titled {title:?} to open on a new workspace");
+                    return;
+                };
+                Some(window)
+            }
+        };
+        let Some(group) = self.group(window.map(|(handle, _)| handle)) else {
+            tracing::warn!("no workspace group to open a new workspace in");
+            return;
+        };
+
+        let Some(workspace) = self.empty_workspace(group) else {
+            let can_create = group.capabilities.contains(&WEnum::Value(
+                ZcosmicWorkspaceGroupCapabilitiesV1::CreateWorkspace,
+            ));
+            if pending.created || !can_create {
+                tracing::warn!("no empty workspace to open {:?} on", pending.target);
+                return;
+            }
+            // Opened once the compositor announces the new workspace.
+            group.handle.create_workspace(String::new());
+            manager.commit();
+            self.pending = Some(Pending {
+                created: true,
+                ..pending
+            });
+            let _ = self.conn.flush();
+            return;
+        };
+
+        if let Some((handle, output)) = window {
+            let Some(output) = output.or_else(|| group.outputs.first()) else {
+                return;
+            };
+            self.toplevel_manager_state
+                .manager
+                .move_to_workspace(handle, &workspace, output);
+        }
+        workspace.activate();
+        manager.commit();
+        let _ = self.conn.flush();
+    }
+}
+
+impl ProvidesRegistryState for State {
+    fn registry(&mut self) -> &mut RegistryState {
+        &mut self.registry_state
+    }
+
+    sctk::registry_handlers!(OutputState);
+}
+
+impl OutputHandler for State {
+    fn output_state(&mut self) -> &mut OutputState {
+        &mut self.output_state
+    }
+
+    fn new_output(&mut self, _: &Connection, _: &QueueHandle<Self>, _: WlOutput) {}
+
+    fn update_output(&mut self, _: &Connection, _: &QueueHandle<Self>, _: WlOutput) {}
+
+    fn output_destroyed(&mut self, _: &Connection, _: &QueueHandle<Self>, _: WlOutput) {}
+}
+
+impl WorkspaceHandler for State {
+    fn workspace_state(&mut self) -> &mut WorkspaceState {
+        &mut self.workspace_state
+    }
+
+    fn done(&mut self) {
+        if self.pending.is_some() {
+            self.open();
+        }
+    }
+}
+
+impl ToplevelInfoHandler for State {
+    fn toplevel_info_state(&mut self) -> &mut ToplevelInfoState {
+        &mut self.toplevel_info_state
+    }
+
+    fn new_toplevel(&mut self, _: &Connection, _: &QueueHandle<Self>, _: &ZcosmicToplevelHandleV1) {
+    }
+
+    fn update_toplevel(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        _: &ZcosmicToplevelHandleV1,
+    ) {
+    }
+
+    fn toplevel_closed(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        _: &ZcosmicToplevelHandleV1,
+    ) {
+    }
+}
+
+impl ToplevelManagerHandler for State {
+    fn toplevel_manager_state(&mut self) -> &mut ToplevelManagerState {
+        &mut self.toplevel_manager_state
+    }
+
+    fn capabilities(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        _: Vec<WEnum<zcosmic_toplevel_manager_v1::ZcosmicToplelevelManagementCapabilitiesV1>>,
+    ) {
+    }
+}
+
+sctk::delegate_output!(State);
+sctk::delegate_registry!(State);
+cctk::delegate_workspace!(State);
+cctk::delegate_toplevel_info!(State);
+cctk::delegate_toplevel_manager!(State);